@@ -0,0 +1,188 @@
+use nalgebra_glm::Vec3;
+use crate::face::Face;
+
+// Caja alineada a los ejes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    pub fn from_center(center: &Vec3, half_extents: &Vec3) -> Self {
+        Aabb {
+            min: center - half_extents,
+            max: center + half_extents,
+        }
+    }
+
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    // Las 8 esquinas de la caja
+    pub fn corners(&self) -> [Vec3; 8] {
+        let (min, max) = (self.min, self.max);
+        [
+            Vec3::new(min.x, min.y, min.z),
+            Vec3::new(max.x, min.y, min.z),
+            Vec3::new(max.x, max.y, min.z),
+            Vec3::new(min.x, max.y, min.z),
+            Vec3::new(min.x, min.y, max.z),
+            Vec3::new(max.x, min.y, max.z),
+            Vec3::new(max.x, max.y, max.z),
+            Vec3::new(min.x, max.y, max.z),
+        ]
+    }
+
+    // Caja que contiene a ambas
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: self.min.inf(&other.min),
+            max: self.max.sup(&other.max),
+        }
+    }
+
+    // Prueba de slabs compartida por las cajas: distancia, cara del choque y si el rayo
+    // empezó adentro (entonces el choque es la salida)
+    pub fn ray_intersect(&self, ray_origin: &Vec3, ray_direction: &Vec3) -> Option<(f32, Face, bool)> {
+        let (min, max) = (&self.min, &self.max);
+        // Componentes ~0 se reemplazan por un epsilon con el mismo signo (rayos paralelos a un eje)
+        let safe_direction = ray_direction.map(|d| {
+            if d.abs() < 1e-8 { 1e-8_f32.copysign(d) } else { d }
+        });
+        let t_min = (min - ray_origin).component_div(&safe_direction);
+        let t_max = (max - ray_origin).component_div(&safe_direction);
+
+        let t_near = t_min.inf(&t_max);
+        let t_far = t_min.sup(&t_max);
+
+        let t_near_val = t_near.x.max(t_near.y).max(t_near.z);
+        let t_far_val = t_far.x.min(t_far.y).min(t_far.z);
+
+        if t_near_val > t_far_val || t_far_val < 0.0 {
+            return None;
+        }
+
+        let inside = t_near_val < 0.0;
+        let t = if inside { t_far_val } else { t_near_val };
+        let point = ray_origin + ray_direction * t;
+        Some((t, calculate_face(&point, min, max), inside))
+    }
+}
+
+// Coordenadas de textura en [0, 1] sobre la cara del choque
+// Las caras opuestas invierten un eje para que, vistas desde afuera, la textura no salga en espejo
+pub fn face_uv(face: Face, point: &Vec3, min: &Vec3, max: &Vec3) -> (f32, f32) {
+    let size = max - min;
+    match face {
+        Face::PositiveX => ((max.z - point.z) / size.z, (point.y - min.y) / size.y), // Cara derecha
+        Face::NegativeX => ((point.z - min.z) / size.z, (point.y - min.y) / size.y), // Cara izquierda
+        Face::PositiveY => ((point.x - min.x) / size.x, (point.z - min.z) / size.z), // Cara superior
+        Face::NegativeY => ((point.x - min.x) / size.x, (max.z - point.z) / size.z), // Cara inferior
+        Face::PositiveZ => ((point.x - min.x) / size.x, (point.y - min.y) / size.y), // Cara frontal
+        Face::NegativeZ => ((max.x - point.x) / size.x, (point.y - min.y) / size.y), // Cara trasera
+    }
+}
+
+// dp/du y dp/dv de face_uv: los ejes de la cara por el tamaño de la caja en ese eje
+pub fn face_uv_derivatives(face: Face, min: &Vec3, max: &Vec3) -> (Vec3, Vec3) {
+    let size = max - min;
+    let (u_axis, v_axis) = face.uv_axes();
+    (u_axis * u_axis.abs().dot(&size), v_axis * v_axis.abs().dot(&size))
+}
+
+// Cara donde cae el punto
+fn calculate_face(point: &Vec3, min: &Vec3, max: &Vec3) -> Face {
+    let center = (min + max) / 2.0;
+    let half_extents = (max - min) / 2.0;
+    let x_diff = (point.x - center.x).abs() - half_extents.x;
+    let y_diff = (point.y - center.y).abs() - half_extents.y;
+    let z_diff = (point.z - center.z).abs() - half_extents.z;
+
+    // Cara más cercana
+    if x_diff >= y_diff && x_diff >= z_diff {
+        if point.x > center.x {
+            Face::PositiveX // Cara derecha
+        } else {
+            Face::NegativeX // Cara izquierda
+        }
+    } else if y_diff >= x_diff && y_diff >= z_diff {
+        if point.y > center.y {
+            Face::PositiveY // Cara superior
+        } else {
+            Face::NegativeY // Cara inferior
+        }
+    } else if point.z > center.z {
+        Face::PositiveZ // Cara frontal
+    } else {
+        Face::NegativeZ // Cara trasera
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // u de un punto a la derecha del centro de la cara, visto desde afuera con y hacia arriba
+    fn u_right_of_center(face: Face) -> f32 {
+        let (min, max) = (Vec3::new(-1.0, -1.0, -1.0), Vec3::new(1.0, 1.0, 1.0));
+        let outward = face.normal();
+        let right = (-outward).cross(&Vec3::new(0.0, 1.0, 0.0));
+        face_uv(face, &(outward + right * 0.5), &min, &max).0
+    }
+
+    fn unit_box() -> Aabb {
+        Aabb::from_center(&Vec3::zeros(), &Vec3::new(1.0, 1.0, 1.0))
+    }
+
+    #[test]
+    fn union_contains_both_boxes() {
+        let other = Aabb::from_center(&Vec3::new(3.0, -2.0, 0.5), &Vec3::new(0.5, 0.5, 0.5));
+        let union = unit_box().union(&other);
+        assert_eq!(union.min, Vec3::new(-1.0, -2.5, -1.0));
+        assert_eq!(union.max, Vec3::new(3.5, 1.0, 1.0));
+        assert_eq!(union, other.union(&unit_box()));
+    }
+
+    #[test]
+    fn ray_hits_the_facing_side() {
+        let hit = unit_box().ray_intersect(&Vec3::new(0.2, 0.3, 5.0), &Vec3::new(0.0, 0.0, -1.0));
+        let (t, face, inside) = hit.unwrap();
+        assert!((t - 4.0).abs() < 1e-5);
+        assert_eq!(face, Face::PositiveZ);
+        assert!(!inside);
+    }
+
+    #[test]
+    fn ray_misses_beside_or_behind() {
+        let beside = unit_box().ray_intersect(&Vec3::new(1.5, 0.0, 5.0), &Vec3::new(0.0, 0.0, -1.0));
+        assert_eq!(beside, None);
+        let behind = unit_box().ray_intersect(&Vec3::new(0.0, 0.0, 5.0), &Vec3::new(0.0, 0.0, 1.0));
+        assert_eq!(behind, None);
+    }
+
+    #[test]
+    fn ray_from_inside_exits() {
+        let (t, face, inside) = unit_box().ray_intersect(&Vec3::zeros(), &Vec3::new(1.0, 0.0, 0.0)).unwrap();
+        assert!((t - 1.0).abs() < 1e-5);
+        assert_eq!(face, Face::PositiveX);
+        assert!(inside);
+    }
+
+    #[test]
+    fn opposite_x_faces_are_symmetric() {
+        let positive = u_right_of_center(Face::PositiveX);
+        let negative = u_right_of_center(Face::NegativeX);
+        assert!((positive - 0.75).abs() < 1e-5); //u crece hacia la derecha del observador
+        assert!((positive - negative).abs() < 1e-5);
+    }
+
+    #[test]
+    fn opposite_z_faces_are_symmetric() {
+        let positive = u_right_of_center(Face::PositiveZ);
+        let negative = u_right_of_center(Face::NegativeZ);
+        assert!((positive - 0.75).abs() < 1e-5);
+        assert!((positive - negative).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,92 @@
+use nalgebra_glm::Vec3;
+use crate::framebuffer::Framebuffer;
+use crate::tone_mapping::ToneMapping;
+
+// Acumulación temporal: con la camara quieta cada cuadro suma sus muestras al
+// promedio de los anteriores y el ruido baja poco a poco
+pub struct Accumulator {
+    sum: Vec<Vec3>,
+    frames: u32,
+    samples: u32, //muestras por pixel acumuladas
+}
+
+impl Accumulator {
+    pub fn new() -> Self {
+        Accumulator {
+            sum: Vec::new(),
+            frames: 0,
+            samples: 0,
+        }
+    }
+
+    // Empezar de nuevo (cambió la camara, la luz o la configuración)
+    pub fn reset(&mut self) {
+        self.sum.clear();
+        self.frames = 0;
+        self.samples = 0;
+    }
+
+    // Cuadros acumulados; sumado a la semilla base cada cuadro tiene otro ruido
+    pub fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    pub fn sample_count(&self) -> u32 {
+        self.samples
+    }
+
+    // Suma el HDR del cuadro recién renderizado y deja el promedio en el framebuffer
+    pub fn accumulate(&mut self, framebuffer: &mut Framebuffer, samples_per_frame: u32, tone_mapping: ToneMapping, gamma: f32) {
+        if self.sum.len() != framebuffer.hdr.len() {
+            self.reset();
+            self.sum = vec![Vec3::zeros(); framebuffer.hdr.len()];
+        }
+
+        self.frames += 1;
+        self.samples += samples_per_frame;
+        let frames = self.frames as f32;
+        for (sum, color) in self.sum.iter_mut().zip(framebuffer.hdr.iter_mut()) {
+            *sum += *color;
+            *color = *sum / frames;
+        }
+        framebuffer.tone_map(tone_mapping, gamma);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    // Ruido uniforme en [0, 1) alrededor de un gris medio
+    fn noisy_frame(framebuffer: &mut Framebuffer, seed: u64) {
+        let mut rng = Rng::new(seed);
+        for pixel in framebuffer.hdr.iter_mut() {
+            let value = rng.next_f32();
+            *pixel = Vec3::new(value, value, value);
+        }
+    }
+
+    fn variance(values: &[Vec3]) -> f32 {
+        let mean = values.iter().map(|v| v.x).sum::<f32>() / values.len() as f32;
+        values.iter().map(|v| (v.x - mean) * (v.x - mean)).sum::<f32>() / values.len() as f32
+    }
+
+    #[test]
+    fn two_frames_are_less_noisy_than_one() {
+        let mut framebuffer = Framebuffer::new(32, 32);
+        let mut accumulator = Accumulator::new();
+
+        noisy_frame(&mut framebuffer, 1);
+        accumulator.accumulate(&mut framebuffer, 1, ToneMapping::Clamp, 1.0);
+        let one = variance(&framebuffer.hdr);
+
+        noisy_frame(&mut framebuffer, 2);
+        accumulator.accumulate(&mut framebuffer, 1, ToneMapping::Clamp, 1.0);
+        let two = variance(&framebuffer.hdr);
+
+        assert_eq!(accumulator.frames(), 2);
+        assert_eq!(accumulator.sample_count(), 2);
+        assert!(two < one * 0.75); //el promedio de dos cuadros independientes tiene la mitad
+    }
+}
@@ -0,0 +1,78 @@
+use nalgebra_glm::Vec3;
+use crate::sampling::{cosine_weighted_hemisphere, spiral_2d};
+
+pub struct AmbientOcclusion {
+    pub samples: u32,
+    pub radius: f32,
+}
+
+impl AmbientOcclusion {
+    pub fn new(samples: u32, radius: f32) -> Self {
+        AmbientOcclusion {
+            samples,
+            radius,
+        }
+    }
+
+    // Fraccion de rayos del hemisferio que chocan con algo dentro del radio
+    // `hit_distance` devuelve la distancia al objeto más cercano en esa dirección
+    // `rotation` en [0, 1) gira el patrón de muestras
+    pub fn occlusion<F>(&self, point: &Vec3, normal: &Vec3, rotation: f32, hit_distance: F) -> f32
+    where
+        F: Fn(&Vec3, &Vec3) -> Option<f32>,
+    {
+        if self.samples == 0 {
+            return 0.0;
+        }
+
+        let origin = point + normal * 1e-3; // Evitar chocar con la misma superficie
+
+        let mut hits = 0;
+        for i in 0..self.samples {
+            let (u1, u2) = spiral_2d(i, self.samples, rotation);
+            let direction = cosine_weighted_hemisphere(normal, u1, u2);
+
+            if let Some(distance) = hit_distance(&origin, &direction) {
+                if distance < self.radius {
+                    hits += 1;
+                }
+            }
+        }
+
+        hits as f32 / self.samples as f32
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::Cube;
+    use crate::material::Material;
+    use crate::scene::Scene;
+
+    #[test]
+    fn point_between_cubes_is_more_occluded_than_an_open_one() {
+        //Cuatro cubos alrededor de (0, 0, 0) sobre el piso y = 0
+        let mut scene = Scene::new();
+        for (x, z) in [(0.6, 0.0), (-0.6, 0.0), (0.0, 0.6), (0.0, -0.6)] {
+            scene.add_object(Box::new(Cube {
+                center: Vec3::new(x, 0.5, z),
+                side_length: 1.0,
+                material: Material::black(),
+                enabled: true,
+            }));
+        }
+        let hit_distance = |origin: &Vec3, direction: &Vec3| {
+            let hit = scene.nearest_hit(origin, direction);
+            hit.is_intersecting.then_some(hit.distance)
+        };
+        let ao = AmbientOcclusion::new(32, 1.0);
+        let up = Vec3::new(0.0, 1.0, 0.0);
+
+        let deep = ao.occlusion(&Vec3::zeros(), &up, 0.0, hit_distance);
+        let exposed = ao.occlusion(&Vec3::new(5.0, 0.0, 5.0), &up, 0.0, hit_distance);
+        assert_eq!(exposed, 0.0);
+        assert!(deep > 0.5, "{}", deep);
+    }
+}
@@ -0,0 +1,64 @@
+use nalgebra_glm::Vec3;
+use std::f32::consts::PI;
+use std::sync::Arc;
+use crate::color::Color;
+use crate::material::Texture;
+
+pub enum Background {
+    Solid(Color),
+    Gradient { horizon: Color, zenith: Color },
+    Environment(Arc<Texture>), //textura equirectangular
+}
+
+impl Background {
+    // Color HDR para un rayo que no chocó con nada
+    pub fn sample(&self, direction: &Vec3) -> Vec3 {
+        match self {
+            Background::Solid(color) => color.to_vec3(),
+            Background::Gradient { horizon, zenith } => {
+                // Mezcla en lineal: en sRGB el medio del degradado queda más oscuro
+                let t = direction.normalize().y.clamp(0.0, 1.0);
+                Color::from_linear(&(horizon.to_linear() * (1.0 - t) + zenith.to_linear() * t)).to_vec3()
+            }
+            Background::Environment(texture) => {
+                let direction = direction.normalize();
+                let u = 0.5 + direction.z.atan2(direction.x) / (2.0 * PI);
+                let v = 0.5 - direction.y.clamp(-1.0, 1.0).asin() / PI;
+                texture.sample(u, v)
+                    .map_or(Vec3::zeros(), |texel| Color::new(texel[0], texel[1], texel[2]).to_vec3())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solid_is_the_same_in_every_direction() {
+        let background = Background::Solid(Color::new(9, 20, 55));
+        for direction in [Vec3::new(0.0, 1.0, 0.0), Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.3, -0.8, 0.2)] {
+            assert_eq!(background.sample(&direction), Color::new(9, 20, 55).to_vec3());
+        }
+    }
+
+    #[test]
+    fn gradient_goes_from_horizon_to_zenith() {
+        let (horizon, zenith) = (Color::new(200, 120, 40), Color::new(10, 30, 90));
+        let background = Background::Gradient { horizon, zenith };
+        assert_eq!(background.sample(&Vec3::new(0.0, 3.0, 0.0)), zenith.to_vec3());
+        assert_eq!(background.sample(&Vec3::new(0.0, 0.0, -1.0)), horizon.to_vec3());
+        assert_eq!(background.sample(&Vec3::new(1.0, -1.0, 0.0)), horizon.to_vec3()); //debajo del horizonte
+    }
+
+    #[test]
+    fn environment_maps_up_to_the_top_row() {
+        let sky = Color::new(80, 160, 240);
+        let ground = Color::new(60, 40, 20);
+        let texture = Texture::from_fn(8, 4, |_, y| if y < 2 { sky } else { ground });
+        let background = Background::Environment(Arc::new(texture));
+        assert_eq!(background.sample(&Vec3::new(0.0, 1.0, 0.0)), sky.to_vec3());
+        assert_eq!(background.sample(&Vec3::new(0.0, -1.0, 0.0)), ground.to_vec3());
+    }
+}
@@ -0,0 +1,60 @@
+use std::time::{Duration, Instant};
+use crate::offscreen::OffscreenRenderer;
+
+// Resultado de --bench: solo cuenta los rayos primarios lanzados (sombras, AO y rebotes no)
+pub struct BenchReport {
+    pub iterations: usize,
+    pub total: Duration,
+    pub primary_rays: u64,
+}
+
+impl BenchReport {
+    pub fn rays_per_second(&self) -> f64 {
+        self.primary_rays as f64 / self.total.as_secs_f64().max(f64::EPSILON)
+    }
+
+    pub fn frame_time(&self) -> Duration {
+        self.total / self.iterations.max(1) as u32
+    }
+}
+
+// Renderiza la misma escena `iterations` veces
+pub fn run_bench(renderer: &mut OffscreenRenderer, iterations: usize) -> BenchReport {
+    let mut primary_rays = 0;
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        renderer.render_frame();
+        primary_rays += renderer.primary_rays;
+    }
+
+    BenchReport {
+        iterations,
+        total: start.elapsed(),
+        primary_rays,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra_glm::Vec3;
+    use crate::camera::Camera;
+    use crate::color::Color;
+    use crate::light::Light;
+    use crate::render_settings::{AdaptiveSampling, RenderSettings};
+    use crate::scene::Scene;
+
+    #[test]
+    fn counts_only_the_rays_cast_with_adaptive_sampling() {
+        // Fondo liso: todos los pixeles convergen en el mínimo de muestras
+        let mut settings = RenderSettings::new();
+        settings.adaptive_sampling = Some(AdaptiveSampling::new(2, 8, 0.0));
+        let camera = Camera::new(Vec3::new(0.0, 0.0, 5.0), Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+        let light = Light::new(Vec3::new(0.0, 5.0, 0.0), Color::new(255, 255, 255), 1.0);
+        let mut renderer = OffscreenRenderer::new(8, 8, Scene::new(), camera, light, settings).unwrap();
+
+        let report = run_bench(&mut renderer, 3);
+        assert_eq!(report.primary_rays, 8 * 8 * 2 * 3);
+    }
+}
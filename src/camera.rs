@@ -1,12 +1,35 @@
 
 use nalgebra_glm::Vec3;
 use std::f32::consts::PI;
+use crate::aabb::Aabb;
+
+const MIN_FOV: f32 = PI / 180.0;
+//orbit no pasa de este ángulo sobre o bajo el horizonte (cerca de 90° forward y up se alinean)
+const MAX_PITCH: f32 = PI / 2.0 - 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection {
+    Perspective,
+    Orthographic,
+}
+
+// Encuadre predefinido (ver camera_presets en main)
+pub struct CameraPreset {
+    pub name: &'static str,
+    pub eye: Vec3,
+    pub center: Vec3,
+}
 
 pub struct Camera {
     pub eye: Vec3,
     pub center: Vec3,
     pub up: Vec3,
-    pub zoom: f32
+    pub projection: Projection,
+    pub ortho_scale: f32, //media altura visible en modo ortografico
+    pub min_radius: f32, //distancia mínima y máxima al centro con zoom
+    pub max_radius: f32,
+    fov: f32, //campo de visión vertical en radianes
+    home: (Vec3, Vec3, Vec3) //eye, center y up iniciales
 }
 
 impl Camera {
@@ -15,18 +38,111 @@ impl Camera {
             eye,
             center,
             up,
-            zoom: 1.0
+            projection: Projection::Perspective,
+            ortho_scale: 3.0,
+            min_radius: 0.5,
+            max_radius: 40.0,
+            fov: PI / 3.0,
+            home: (eye, center, up)
+        }
+    }
+
+    pub fn fov(&self) -> f32 {
+        self.fov
+    }
+
+    //Valores <= 0 o >= PI se recortan a un rango válido
+    pub fn set_fov(&mut self, fov: f32) {
+        if fov.is_nan() {
+            return;
         }
+        self.fov = fov.clamp(MIN_FOV, PI - MIN_FOV);
+    }
+
+    //Volver al encuadre inicial
+    pub fn reset(&mut self) {
+        let (eye, center, up) = self.home;
+        self.eye = eye;
+        self.center = center;
+        self.up = up;
+    }
+
+    //Saltar a un encuadre predefinido, conservando up
+    pub fn apply_preset(&mut self, preset: &CameraPreset) {
+        self.eye = preset.eye;
+        self.center = preset.center;
+    }
+
+    //Base ortonormal de la camara
+    pub fn forward(&self) -> Vec3 {
+        (self.center - self.eye).normalize()
+    }
+
+    pub fn right(&self) -> Vec3 {
+        self.forward().cross(&self.up).normalize()
+    }
+
+    pub fn up(&self) -> Vec3 {
+        self.right().cross(&self.forward()).normalize()
+    }
+
+    pub fn look_at(&mut self, target: Vec3) {
+        self.center = target;
     }
 
     pub fn base_change(&self, vector: &Vec3) -> Vec3 {
-        let forward = (self.center - self.eye).normalize();
-        let right = forward.cross(&self.up).normalize();
-        let up = right.cross(&forward).normalize();
+        let forward = self.forward();
+        let right = self.right();
+        let up = self.up();
 
         let rotated = vector.x * right + vector.y * up - vector.z * forward;
 
-        return rotated.normalize();
+        rotated.normalize()
+    }
+
+    //Media anchura y media altura visibles en ortográfica; el aspecto solo
+    //ensancha el eje horizontal para que los objetos no se deformen
+    pub fn ortho_half_extents(&self, aspect_ratio: f32) -> (f32, f32) {
+        (aspect_ratio * self.ortho_scale, self.ortho_scale)
+    }
+
+    //Inverso de los rayos de render: punto del mundo a coordenadas de pantalla en [-1, 1]
+    //None si el punto queda detrás de la camara
+    pub fn project(&self, point: &Vec3, aspect_ratio: f32) -> Option<(f32, f32)> {
+        let relative = point - self.eye;
+        let x = relative.dot(&self.right());
+        let y = relative.dot(&self.up());
+        let z = relative.dot(&self.forward());
+
+        match self.projection {
+            Projection::Perspective => {
+                if z <= 1e-3 {
+                    return None;
+                }
+                let scale = (self.fov * 0.5).tan();
+                Some((x / (z * aspect_ratio * scale), y / (z * scale)))
+            }
+            Projection::Orthographic => {
+                let (half_width, half_height) = self.ortho_half_extents(aspect_ratio);
+                Some((x / half_width, y / half_height))
+            }
+        }
+    }
+
+    //mover eye y center juntos en ejes locales de la camara
+    pub fn translate(&mut self, offset: Vec3) {
+        let movement = offset.x * self.right() + offset.y * self.up() - offset.z * self.forward();
+
+        self.eye += movement;
+        self.center += movement;
+    }
+
+    //Desplazar el pivote: eye y center se mueven juntos sobre right y up
+    pub fn pan(&mut self, dx: f32, dy: f32) {
+        let movement = dx * self.right() + dy * self.up();
+
+        self.eye += movement;
+        self.center += movement;
     }
 
     pub fn orbit(&mut self, delta_yaw: f32, delta_pitch: f32) {
@@ -38,7 +154,7 @@ impl Camera {
         let current_pitch = (-radius_vector.y).atan2(radius_xz);
 
         let new_yaw = (current_yaw + delta_yaw) % (2.0 * PI);
-        let new_pitch = (current_pitch + delta_pitch).clamp(-PI / 2.0 + 0.1, PI / 2.0 - 0.1);
+        let new_pitch = (current_pitch + delta_pitch).clamp(-MAX_PITCH, MAX_PITCH);
 
         let new_eye = self.center + Vec3::new(
             radius * new_yaw.cos() * new_pitch.cos(),
@@ -47,11 +163,231 @@ impl Camera {
         );
 
         self.eye = new_eye;
+
+        //Con roll, up puede quedar casi paralelo a la nueva vista: volver a hacerlo perpendicular
+        let forward = self.forward();
+        if forward.dot(&self.up.normalize()).abs() > MAX_PITCH.sin() {
+            self.up = (self.up - forward * forward.dot(&self.up)).normalize();
+        }
+    }
+    //Inclinar el horizonte: gira up alrededor del eje de la vista
+    pub fn roll(&mut self, angle: f32) {
+        let forward = self.forward();
+        let up = self.up(); //ya perpendicular a forward
+        self.up = (up * angle.cos() + forward.cross(&up) * angle.sin()).normalize();
+    }
+
+    //Encuadrar la caja completa sin cambiar la dirección de la vista
+    //Se usa la esfera que la contiene, así cabe desde cualquier ángulo; con aspecto < 1
+    //el campo horizontal es el más angosto y es el que manda
+    pub fn frame(&mut self, bounds: &Aabb, fov: f32, aspect_ratio: f32) {
+        self.set_fov(fov);
+        let center = bounds.center();
+        let radius = ((bounds.max - bounds.min).magnitude() * 0.5).max(1e-3);
+        let narrow = aspect_ratio.min(1.0);
+        let half_angle = (narrow * (self.fov * 0.5).tan()).atan();
+        let distance = radius / half_angle.sin();
+
+        self.eye = center - self.forward() * distance;
+        self.center = center;
+        self.max_radius = self.max_radius.max(distance);
+        self.ortho_scale = radius / narrow;
+    }
+
+    pub fn toggle_projection(&mut self) {
+        self.projection = match self.projection {
+            Projection::Perspective => Projection::Orthographic,
+            Projection::Orthographic => Projection::Perspective,
+        };
     }
+
     pub fn adjust_zoom(&mut self, zoom_factor: f32) {
-        self.zoom *= zoom_factor;
+        //Sin pasar a través del centro ni alejarse sin límite; se parte de la distancia
+        //real porque pan, translate y frame mueven eye
+        let offset = self.eye - self.center;
+        let radius = (offset.magnitude() * zoom_factor).clamp(self.min_radius, self.max_radius);
+
+        self.eye = self.center + offset.normalize() * radius;
+    }
+}
+
+// Convierte el arrastre del mouse (pixeles) en (yaw, pitch) para orbit
+pub fn drag_to_orbit(delta_x: f32, delta_y: f32, sensitivity: f32) -> (f32, f32) {
+    (-delta_x * sensitivity, delta_y * sensitivity)
+}
+
+// Arrastre con Shift: la escena sigue al mouse, más rápido cuanto más lejos está el pivote
+pub fn drag_to_pan(delta_x: f32, delta_y: f32, sensitivity: f32, distance: f32) -> (f32, f32) {
+    (-delta_x * sensitivity * distance, delta_y * sensitivity * distance)
+}
+
+// Siguiente preset; después del último vuelve al primero
+pub fn next_preset(current: Option<usize>, count: usize) -> usize {
+    match current {
+        Some(index) if count > 0 => (index + 1) % count,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zoom_starts_from_the_real_distance() {
+        let mut camera = Camera::new(Vec3::new(0.0, 0.0, 5.0), Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+        camera.pan(1.0, 0.0);
+        camera.eye = camera.center + Vec3::new(0.0, 0.0, 2.0);
+        camera.adjust_zoom(0.5);
+        assert!(((camera.eye - camera.center).magnitude() - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn framed_corners_stay_on_screen_in_both_orientations() {
+        let bounds = Aabb::from_center(&Vec3::new(1.0, -0.5, 2.0), &Vec3::new(2.0, 1.0, 1.5));
+        for aspect_ratio in [16.0 / 9.0, 9.0 / 16.0] {
+            for projection in [Projection::Perspective, Projection::Orthographic] {
+                let mut camera = Camera::new(Vec3::new(3.0, 4.0, 8.0), Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+                camera.projection = projection;
+                camera.frame(&bounds, PI / 3.0, aspect_ratio);
+                for corner in bounds.corners() {
+                    let (x, y) = camera.project(&corner, aspect_ratio).unwrap();
+                    assert!(x.abs() <= 1.0 && y.abs() <= 1.0, "{:?} {} {:?}", projection, aspect_ratio, (x, y));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn zooming_in_stops_at_min_radius() {
+        let mut camera = Camera::new(Vec3::new(0.0, 0.0, 5.0), Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+        for _ in 0..100 {
+            camera.adjust_zoom(0.8);
+            assert!((camera.eye - camera.center).magnitude() >= camera.min_radius - 1e-5);
+        }
+        assert!(((camera.eye - camera.center).magnitude() - camera.min_radius).abs() < 1e-5);
+        assert!(camera.forward().z < 0.0); //no pasó al otro lado del centro
+    }
+
+    #[test]
+    fn reset_after_orbit_restores_the_eye() {
+        let eye = Vec3::new(0.0, 2.0, 5.0);
+        let mut camera = Camera::new(eye, Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+        camera.orbit(0.7, -0.3);
+        assert!((camera.eye - eye).magnitude() > 0.1);
+        camera.reset();
+        assert_eq!(camera.eye, eye);
+        assert_eq!(camera.center, Vec3::zeros());
+    }
 
-        let direction = (self.eye - self.center).normalize();
-        self.eye = self.center + direction * (self.zoom * 5.0); //distancia
+    #[test]
+    fn forward_points_from_eye_to_center() {
+        let mut camera = Camera::new(Vec3::new(1.0, 2.0, 3.0), Vec3::new(4.0, 2.0, -1.0), Vec3::new(0.0, 1.0, 0.0));
+        assert!((camera.forward() - Vec3::new(0.6, 0.0, -0.8)).magnitude() < 1e-5);
+        assert!(camera.forward().dot(&camera.right()).abs() < 1e-5);
+        assert!(camera.forward().dot(&camera.up()).abs() < 1e-5);
+
+        camera.look_at(Vec3::new(1.0, 2.0, 0.0));
+        assert!((camera.forward() - Vec3::new(0.0, 0.0, -1.0)).magnitude() < 1e-5);
+    }
+
+    #[test]
+    fn wider_fov_projects_smaller() {
+        let mut camera = Camera::new(Vec3::new(0.0, 0.0, 5.0), Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+        let edge = Vec3::new(0.5, 0.5, 0.0);
+        camera.set_fov(PI / 6.0);
+        let (narrow_x, narrow_y) = camera.project(&edge, 1.0).unwrap();
+        camera.set_fov(PI / 2.0);
+        let (wide_x, wide_y) = camera.project(&edge, 1.0).unwrap();
+        assert!(wide_x < narrow_x && wide_y < narrow_y);
+
+        camera.set_fov(0.0);
+        assert_eq!(camera.fov(), MIN_FOV);
+        camera.set_fov(4.0);
+        assert_eq!(camera.fov(), PI - MIN_FOV);
+        camera.set_fov(f32::NAN);
+        assert_eq!(camera.fov(), PI - MIN_FOV); //NaN no cambia nada
+    }
+
+    #[test]
+    fn pan_right_moves_center_along_right() {
+        let mut camera = Camera::new(Vec3::new(3.0, 2.0, 4.0), Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+        let (eye, center, right) = (camera.eye, camera.center, camera.right());
+        camera.pan(1.5, 0.0);
+        assert!((camera.center - (center + right * 1.5)).magnitude() < 1e-5);
+        assert!((camera.eye - (eye + right * 1.5)).magnitude() < 1e-5);
+        assert!((camera.forward() - (center - eye).normalize()).magnitude() < 1e-5);
+
+        //Arrastrar a la izquierda mueve la escena con el mouse: la camara va a la derecha
+        let (dx, dy) = drag_to_pan(-10.0, 0.0, 0.01, 2.0);
+        assert!((dx - 0.2).abs() < 1e-6 && dy == 0.0);
+        let (_, dy) = drag_to_pan(0.0, 10.0, 0.01, 2.0);
+        assert!((dy - 0.2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn orbiting_up_never_aligns_forward_with_up() {
+        let mut camera = Camera::new(Vec3::new(0.0, 0.0, 5.0), Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+        for _ in 0..50 {
+            camera.orbit(0.0, -PI / 10.0);
+            let alignment = camera.forward().dot(&camera.up.normalize()).abs();
+            assert!(alignment <= MAX_PITCH.sin() + 1e-4, "{}", alignment);
+            assert!(camera.right().iter().all(|c| c.is_finite()));
+        }
+        assert!(camera.eye.y > 0.0); //se quedó arriba, sin cruzar el polo
+    }
+
+    #[test]
+    fn rolling_by_pi_flips_up() {
+        let mut camera = Camera::new(Vec3::new(0.0, 1.0, 5.0), Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+        let up = camera.up();
+        camera.roll(PI);
+        assert!((camera.up() + up).magnitude() < 1e-5);
+        //La base sigue siendo ortonormal
+        let (forward, right, up) = (camera.forward(), camera.right(), camera.up());
+        assert!(forward.dot(&right).abs() < 1e-5 && forward.dot(&up).abs() < 1e-5 && right.dot(&up).abs() < 1e-5);
+    }
+
+    #[test]
+    fn translating_forward_gets_closer_to_what_is_ahead() {
+        let mut camera = Camera::new(Vec3::new(2.0, 1.0, 6.0), Vec3::new(2.0, 1.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+        let ahead = Vec3::new(2.0, 1.0, -3.0);
+        let before = (ahead - camera.eye).magnitude();
+        camera.translate(Vec3::new(0.0, 0.0, -1.0)); //-z es adelante, como en base_change
+        assert!(((ahead - camera.eye).magnitude() - (before - 1.0)).abs() < 1e-5);
+        assert_eq!(camera.center, Vec3::new(2.0, 1.0, -1.0));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn drag_delta_scales_into_orbit_angles() {
+        assert_eq!(drag_to_orbit(0.0, 0.0, 0.01), (0.0, 0.0));
+        let (yaw, pitch) = drag_to_orbit(20.0, -10.0, 0.01);
+        assert!((yaw + 0.2).abs() < 1e-6);
+        assert!((pitch + 0.1).abs() < 1e-6);
+        //El doble de arrastre gira el doble
+        let (double_yaw, _) = drag_to_orbit(40.0, 0.0, 0.01);
+        assert!((double_yaw - 2.0 * yaw).abs() < 1e-6);
+    }
+
+    #[test]
+    fn next_preset_wraps_to_the_first() {
+        assert_eq!(next_preset(None, 3), 0);
+        assert_eq!(next_preset(Some(0), 3), 1);
+        assert_eq!(next_preset(Some(2), 3), 0);
+        assert_eq!(next_preset(Some(1), 0), 0);
+    }
+
+    #[test]
+    fn orthographic_size_does_not_depend_on_depth() {
+        let mut camera = Camera::new(Vec3::new(0.0, 0.0, 5.0), Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+        let projected_width = |camera: &Camera, z: f32| {
+            let (left, _) = camera.project(&Vec3::new(-0.5, 0.0, z), 1.5).unwrap();
+            let (right, _) = camera.project(&Vec3::new(0.5, 0.0, z), 1.5).unwrap();
+            right - left
+        };
+        assert!(projected_width(&camera, 2.0) > projected_width(&camera, -4.0));
+
+        camera.projection = Projection::Orthographic;
+        assert!((projected_width(&camera, 2.0) - projected_width(&camera, -4.0)).abs() < 1e-6);
+    }
+}
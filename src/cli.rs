@@ -0,0 +1,187 @@
+use crate::render_settings::Crop;
+
+// Escenas incluidas, se eligen con --scene
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BuiltinScene {
+    Default, //el diorama
+    Empty,
+    Cornell, //caja cerrada para probar iluminación
+}
+
+impl BuiltinScene {
+    pub const NAMES: [&'static str; 3] = ["default", "empty", "cornell"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(BuiltinScene::Default),
+            "empty" => Some(BuiltinScene::Empty),
+            "cornell" => Some(BuiltinScene::Cornell),
+            _ => None,
+        }
+    }
+}
+
+// Opciones de línea de comandos
+pub struct CliOptions {
+    pub width: usize,
+    pub height: usize,
+    pub headless_output: Option<String>,
+    pub depth_output: Option<String>, //con --headless, profundidad en escala de grises
+    pub verbose: bool,
+    pub render_scale: f32, //resolución interna relativa a la ventana
+    pub bench_iterations: Option<usize>, //renderizar N veces sin ventana y medir
+    pub turntable_output: Option<String>, //carpeta para la vuelta de 360°, "-" = stdout
+    pub turntable_frames: usize,
+    pub max_texture_size: Option<u32>, //lado máximo de las texturas al cargarlas
+    pub crop: Option<Crop>, //--crop x,y,ancho,alto
+    pub scene: BuiltinScene,
+    pub samples: Option<u32>, //muestras por pixel, None = las de RenderSettings
+    pub depth: Option<u32>, //rebotes de reflexión/refracción
+    pub bloom: bool, //empezar con bloom (también se activa con B)
+    pub shadow_samples: Option<u32>, //rayos de sombra por choque, mayor a 1 = sombras suaves
+    pub model: Option<String>, //.obj que se agrega a la escena
+    pub environment: Option<String>, //imagen equirectangular para el cielo
+    pub materials: Option<String>, //biblioteca de materiales para el diorama
+}
+
+impl CliOptions {
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = CliOptions {
+            width: 800,
+            height: 600,
+            headless_output: None,
+            depth_output: None,
+            verbose: false,
+            render_scale: 1.0,
+            bench_iterations: None,
+            turntable_output: None,
+            turntable_frames: 36,
+            max_texture_size: None,
+            crop: None,
+            scene: BuiltinScene::Default,
+            samples: None,
+            depth: None,
+            bloom: false,
+            shadow_samples: None,
+            model: None,
+            environment: None,
+            materials: None,
+        };
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--headless" => options.headless_output = Some(next_value(&mut iter, arg)?.to_string()),
+                "--depth-output" => options.depth_output = Some(next_value(&mut iter, arg)?.to_string()),
+                "--width" => options.width = parse_positive(next_value(&mut iter, arg)?, arg)?,
+                "--height" => options.height = parse_positive(next_value(&mut iter, arg)?, arg)?,
+                "--verbose" => options.verbose = true,
+                "--bloom" => options.bloom = true,
+                "--turntable" => options.turntable_output = Some(next_value(&mut iter, arg)?.to_string()),
+                "--frames" => options.turntable_frames = parse_positive(next_value(&mut iter, arg)?, arg)?,
+                "--max-texture-size" => options.max_texture_size = Some(parse_positive(next_value(&mut iter, arg)?, arg)? as u32),
+                "--crop" => options.crop = Some(parse_crop(next_value(&mut iter, arg)?, arg)?),
+                "--samples" => options.samples = Some(parse_positive(next_value(&mut iter, arg)?, arg)? as u32),
+                "--depth" => options.depth = Some(parse_positive(next_value(&mut iter, arg)?, arg)? as u32),
+                "--shadow-samples" => options.shadow_samples = Some(parse_positive(next_value(&mut iter, arg)?, arg)? as u32),
+                "--model" => options.model = Some(next_value(&mut iter, arg)?.to_string()),
+                "--environment" => options.environment = Some(next_value(&mut iter, arg)?.to_string()),
+                "--materials" => options.materials = Some(next_value(&mut iter, arg)?.to_string()),
+                "--scene" => options.scene = parse_scene(next_value(&mut iter, arg)?, arg)?,
+                "--bench" => options.bench_iterations = Some(parse_positive(next_value(&mut iter, arg)?, arg)?),
+                "--render-scale" => options.render_scale = parse_scale(next_value(&mut iter, arg)?, arg)?,
+                _ => return Err(format!("Argumento desconocido: {}", arg)),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+fn next_value<'a>(iter: &mut std::slice::Iter<'a, String>, flag: &str) -> Result<&'a str, String> {
+    iter.next()
+        .map(|value| value.as_str())
+        .ok_or(format!("Falta el valor de {}", flag))
+}
+
+fn parse_positive(value: &str, flag: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(format!("{} debe ser un entero positivo, se recibió '{}'", flag, value)),
+    }
+}
+
+fn parse_scale(value: &str, flag: &str) -> Result<f32, String> {
+    match value.parse::<f32>() {
+        Ok(scale) if scale > 0.0 && scale <= 1.0 => Ok(scale),
+        _ => Err(format!("{} debe estar en (0, 1], se recibió '{}'", flag, value)),
+    }
+}
+
+// "x,y,ancho,alto" en pixeles; ancho y alto mayores a 0
+fn parse_crop(value: &str, flag: &str) -> Result<Crop, String> {
+    let error = || format!("{} espera x,y,ancho,alto, se recibió '{}'", flag, value);
+    let numbers: Vec<usize> = value.split(',')
+        .map(|part| part.trim().parse::<usize>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| error())?;
+
+    match numbers[..] {
+        [x, y, width, height] if width > 0 && height > 0 => Ok(Crop::new(x, y, width, height)),
+        _ => Err(error()),
+    }
+}
+
+fn parse_scene(value: &str, flag: &str) -> Result<BuiltinScene, String> {
+    BuiltinScene::from_name(value)
+        .ok_or(format!("{} debe ser una de {}, se recibió '{}'", flag, BuiltinScene::NAMES.join(", "), value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliOptions, String> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        CliOptions::parse(&args)
+    }
+
+    #[test]
+    fn samples_and_depth_are_parsed() {
+        let options = parse(&["--samples", "4", "--depth", "3"]).unwrap();
+        assert_eq!(options.samples, Some(4));
+        assert_eq!(options.depth, Some(3));
+    }
+
+    #[test]
+    fn omitted_flags_keep_the_defaults() {
+        let options = parse(&[]).unwrap();
+        assert_eq!(options.samples, None);
+        assert_eq!(options.depth, None);
+        assert_eq!((options.width, options.height), (800, 600));
+        assert_eq!(options.scene, BuiltinScene::Default);
+    }
+
+    #[test]
+    fn invalid_values_are_rejected() {
+        for args in [["--samples", "0"], ["--depth", "-1"], ["--samples", "cuatro"], ["--width", "1.5"]] {
+            assert!(parse(&args).is_err(), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn render_scale_must_be_in_zero_one() {
+        assert_eq!(parse(&["--render-scale", "0.5"]).unwrap().render_scale, 0.5);
+        assert_eq!(parse(&["--render-scale", "1"]).unwrap().render_scale, 1.0);
+        for value in ["0", "-0.5", "1.01", "2", "NaN", "mitad"] {
+            assert!(parse_scale(value, "--render-scale").is_err(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn missing_value_is_reported() {
+        let error = parse(&["--headless", "out.png", "--depth"]).err().unwrap();
+        assert!(error.contains("--depth"));
+        assert!(parse(&["--nada"]).is_err());
+    }
+}
@@ -1,5 +1,6 @@
 use std::fmt; // Asegúrate de que esta línea esté presente
-use std::ops::{Add, AddAssign, Mul};
+use nalgebra_glm::Vec3;
+use std::ops::Mul;
 
 #[derive(Debug, Clone, Copy)]
 pub struct Color {
@@ -20,30 +21,83 @@ impl Color {
         Color { r, g, b }
     }
 
-    pub fn to_hex(&self) -> u32 {
+    pub fn to_hex(self) -> u32 {
         ((self.r as u32) << 16) | ((self.g as u32) << 8) | (self.b as u32)
     }
-}
 
-// Implementar suma
-impl Add for Color {
-    type Output = Color;
+    // 0xAARRGGBB
+    pub fn to_hex_with_alpha(self, alpha: u8) -> u32 {
+        ((alpha as u32) << 24) | self.to_hex()
+    }
+
+    // Color en flotantes, 1.0 = 255
+    pub fn to_vec3(self) -> Vec3 {
+        Vec3::new(self.r as f32, self.g as f32, self.b as f32) / 255.0
+    }
+
+    pub fn from_vec3(color: &Vec3) -> Self {
+        Self::from_rgb_f32(color.x, color.y, color.z)
+    }
 
-    fn add(self, other: Color) -> Color {
+    // Componentes en [0, 1], se cuantizan a u8
+    pub fn from_rgb_f32(r: f32, g: f32, b: f32) -> Self {
+        let quantize = |c: f32| (c * 255.0).round().clamp(0.0, 255.0) as u8;
         Color {
-            r: self.r.saturating_add(other.r),
-            g: self.g.saturating_add(other.g),
-            b: self.b.saturating_add(other.b),
+            r: quantize(r),
+            g: quantize(g),
+            b: quantize(b),
         }
     }
+
+    // Color aproximado de un cuerpo negro (ajuste de Tanner Helland, 1000K a 40000K)
+    // 6500K es casi blanco, valores menores son más cálidos y mayores más fríos
+    pub fn from_kelvin(kelvin: f32) -> Self {
+        let temperature = kelvin.clamp(1000.0, 40000.0) / 100.0;
+
+        let r = if temperature <= 66.0 {
+            255.0
+        } else {
+            329.69873 * (temperature - 60.0).powf(-0.13320476)
+        };
+        let g = if temperature <= 66.0 {
+            99.4708 * temperature.ln() - 161.11957
+        } else {
+            288.12216 * (temperature - 60.0).powf(-0.07551485)
+        };
+        let b = if temperature >= 66.0 {
+            255.0
+        } else if temperature <= 19.0 {
+            0.0
+        } else {
+            138.51773 * (temperature - 10.0).ln() - 305.0448
+        };
+
+        Self::from_rgb_f32(r / 255.0, g / 255.0, b / 255.0)
+    }
+
+    // sRGB (como se guarda) a lineal (para sumar luz)
+    pub fn to_linear(self) -> Vec3 {
+        self.to_vec3().map(srgb_to_linear)
+    }
+
+    pub fn from_linear(color: &Vec3) -> Self {
+        Self::from_vec3(&color.map(linear_to_srgb))
+    }
 }
 
-// Implementar suma asignada
-impl AddAssign for Color {
-    fn add_assign(&mut self, other: Color) {
-        self.r = self.r.saturating_add(other.r);
-        self.g = self.g.saturating_add(other.g);
-        self.b = self.b.saturating_add(other.b);
+pub fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+pub fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
     }
 }
 
@@ -65,4 +119,24 @@ impl fmt::Display for Color {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "Color(r: {}, g: {}, b: {})", self.r, self.g, self.b)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn float_color_round_trips_through_linear() {
+        for c in [0.0, 0.002, 0.04, 0.2, 0.5, 0.8, 1.0] {
+            assert!((linear_to_srgb(srgb_to_linear(c)) - c).abs() < 1e-5, "{}", c);
+        }
+        let color = Color::from_rgb_f32(0.9, 0.35, 0.05);
+        assert_eq!(Color::from_linear(&color.to_linear()).to_hex(), color.to_hex());
+    }
+
+    #[test]
+    fn linear_is_darker_than_srgb_in_the_midtones() {
+        let linear = Color::new(128, 128, 128).to_linear();
+        assert!((linear.x - 0.2158).abs() < 1e-3);
+    }
+}
@@ -1,84 +1,143 @@
 use nalgebra_glm::Vec3;
 use crate::ray_intersect::{RayIntersect, Intersect};
 use crate::material::Material;
+use crate::aabb::{Aabb, face_uv, face_uv_derivatives};
 
 pub struct Cube {
     pub center: Vec3,
     pub side_length: f32,
     pub material: Material,
+    pub enabled: bool,
+}
+
+impl Cube {
+    pub fn bounds(&self) -> Aabb {
+        let half_size = self.side_length / 2.0;
+        Aabb::from_center(&self.center, &Vec3::new(half_size, half_size, half_size))
+    }
 }
 
 impl RayIntersect for Cube {
     fn ray_intersect(&self, ray_origin: &Vec3, ray_direction: &Vec3) -> Intersect {
-        let half_size = self.side_length / 2.0;
-        let min = self.center - Vec3::new(half_size, half_size, half_size);
-        let max = self.center + Vec3::new(half_size, half_size, half_size);
-        
-        let t_min = (min - ray_origin).component_div(ray_direction);
-        let t_max = (max - ray_origin).component_div(ray_direction);
-
-        let t_near = Vec3::new(
-            t_min.x.min(t_max.x),
-            t_min.y.min(t_max.y),
-            t_min.z.min(t_max.z),
-        );
-        let t_far = Vec3::new(
-            t_min.x.max(t_max.x),
-            t_min.y.max(t_max.y),
-            t_min.z.max(t_max.z),
-        );
-
-        let t_near_val = t_near.x.max(t_near.y).max(t_near.z);
-        let t_far_val = t_far.x.min(t_far.y).min(t_far.z);
-
-        if t_near_val > t_far_val || t_far_val < 0.0 {
-            return Intersect::empty(); // No intersección
-        }
+        let bounds = self.bounds();
+        let (min, max) = (bounds.min, bounds.max);
 
-        let t = if t_near_val < 0.0 { t_far_val } else { t_near_val };
+        let (t, face, inside) = match bounds.ray_intersect(ray_origin, ray_direction) {
+            Some(hit) => hit,
+            None => return Intersect::empty(), // No intersección
+        };
         let intersection_point = ray_origin + ray_direction * t;
 
-        let normal = calculate_normal(&intersection_point, &self.center, self.side_length);
-        let u = match normal.x {
-            1.0 => (intersection_point.z - min.z) / self.side_length, // Cara derecha
-            -1.0 => (intersection_point.z - max.z) / self.side_length, // Cara izquierda
-            _ => (intersection_point.x - min.x) / self.side_length, // Para las caras Y y Z
-        };
-        
-        let v = match normal.y {
-            1.0 => (intersection_point.z - min.z) / self.side_length, // Cara superior
-            -1.0 => (intersection_point.z - max.z) / self.side_length, // Cara inferior
-            _ => (intersection_point.y - min.y) / self.side_length, // Para las caras X y Z
-        };
+        // Desde adentro la normal apunta hacia el interior (hacia el origen del rayo)
+        let normal = if inside { -face.normal() } else { face.normal() };
+        let (u, v) = face_uv(face, &intersection_point, &min, &max);
+        let (u, v) = self.material.resolve_uv(u, v);
+        let (dp_du, dp_dv) = face_uv_derivatives(face, &min, &max);
         Intersect::new(intersection_point, normal, t, self.material.clone(), u, v) // Clonar material
+            .with_uv_derivatives(dp_du, dp_dv)
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn translate(&mut self, offset: &Vec3) {
+        self.center += offset;
+    }
+
+    fn update(&mut self, time: f32) {
+        self.material.update(time);
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.bounds())
     }
 }
 
-// Vector normal
-fn calculate_normal(point: &Vec3, center: &Vec3, side_length: f32) -> Vec3 {
-    let half_side = side_length / 2.0;
-    let x_diff = (point.x - center.x).abs() - half_side;
-    let y_diff = (point.y - center.y).abs() - half_side;
-    let z_diff = (point.z - center.z).abs() - half_side;
-
-    // Cara más cercana
-    if x_diff >= y_diff && x_diff >= z_diff {
-        if point.x > center.x {
-            return Vec3::new(1.0, 0.0, 0.0); // Cara derecha
-        } else {
-            return Vec3::new(-1.0, 0.0, 0.0); // Cara izquierda
-        }
-    } else if y_diff >= x_diff && y_diff >= z_diff {
-        if point.y > center.y {
-            return Vec3::new(0.0, 1.0, 0.0); // Cara superior
-        } else {
-            return Vec3::new(0.0, -1.0, 0.0); // Cara inferior
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::material::{Texture, WrapMode};
+
+    fn cube(material: Material) -> Cube {
+        Cube { center: Vec3::zeros(), side_length: 2.0, material, enabled: true }
+    }
+
+    fn hit_top(cube: &Cube, x: f32, z: f32) -> Intersect {
+        cube.ray_intersect(&Vec3::new(x, 5.0, z), &Vec3::new(0.0, -1.0, 0.0))
+    }
+
+    #[test]
+    fn top_face_tangent_follows_u_and_bitangent_follows_v() {
+        let cube = cube(Material::black());
+        let hit = hit_top(&cube, 0.1, 0.2);
+        let (tangent, bitangent, normal) = hit.tbn();
+        assert!((tangent - Vec3::new(1.0, 0.0, 0.0)).magnitude() < 1e-5);
+        assert!((bitangent - Vec3::new(0.0, 0.0, 1.0)).magnitude() < 1e-5); //v crece hacia +z
+        assert!((normal - Vec3::new(0.0, 1.0, 0.0)).magnitude() < 1e-5);
+
+        let step = 0.01;
+        let moved = hit_top(&cube, 0.1 + bitangent.x * step, 0.2 + bitangent.z * step);
+        assert!(moved.v > hit.v && (moved.u - hit.u).abs() < 1e-5);
+    }
+
+    #[test]
+    fn rotated_texture_rotates_the_tangent() {
+        let mut material = Material::black();
+        material.texture_rotation = std::f32::consts::FRAC_PI_2;
+        let cube = cube(material);
+        let hit = hit_top(&cube, 0.1, 0.2);
+        let (tangent, bitangent, _) = hit.tbn();
+
+        // Avanzar sobre la tangente solo cambia u, sobre la bitangente solo v
+        let step = 0.01;
+        let along_u = hit_top(&cube, 0.1 + tangent.x * step, 0.2 + tangent.z * step);
+        assert!(along_u.u > hit.u && (along_u.v - hit.v).abs() < 1e-5);
+        let along_v = hit_top(&cube, 0.1 + bitangent.x * step, 0.2 + bitangent.z * step);
+        assert!(along_v.v > hit.v && (along_v.u - hit.u).abs() < 1e-5);
+    }
+
+    #[test]
+    fn each_face_has_its_point_normal_and_uv() {
+        let cube = cube(Material::black());
+        //(punto en la cara, normal, u, v); el rayo llega desde afuera sobre la normal
+        let faces = [
+            (Vec3::new(1.0, 0.5, -0.5), Vec3::new(1.0, 0.0, 0.0), 0.75, 0.75),
+            (Vec3::new(-1.0, 0.5, -0.5), Vec3::new(-1.0, 0.0, 0.0), 0.25, 0.75),
+            (Vec3::new(0.5, 1.0, -0.5), Vec3::new(0.0, 1.0, 0.0), 0.75, 0.25),
+            (Vec3::new(0.5, -1.0, -0.5), Vec3::new(0.0, -1.0, 0.0), 0.75, 0.75),
+            (Vec3::new(0.5, -0.5, 1.0), Vec3::new(0.0, 0.0, 1.0), 0.75, 0.25),
+            (Vec3::new(0.5, -0.5, -1.0), Vec3::new(0.0, 0.0, -1.0), 0.25, 0.25),
+        ];
+        for (point, normal, u, v) in faces {
+            let hit = cube.ray_intersect(&(point + normal * 4.0), &-normal);
+            assert!(hit.is_intersecting);
+            assert!((hit.point - point).magnitude() < 1e-5, "{:?}", normal);
+            assert!((hit.normal - normal).magnitude() < 1e-5, "{:?}", normal);
+            assert!((hit.u - u).abs() < 1e-5 && (hit.v - v).abs() < 1e-5, "{:?}: ({}, {})", normal, hit.u, hit.v);
         }
-    } else {
-        if point.z > center.z {
-            return Vec3::new(0.0, 0.0, 1.0); // Cara frontal
-        } else {
-            return Vec3::new(0.0, 0.0, -1.0); // Cara trasera
+    }
+
+    #[test]
+    fn uv_scale_repeats_the_texture_across_the_face() {
+        let mut material = Material::black();
+        material.uv_scale = [4.0, 4.0];
+        material.wrap_mode = WrapMode::Repeat;
+        let cube = cube(material);
+        //Cada texel con su propio color
+        let texture = Texture::from_fn(8, 8, |x, y| Color::new(30 * x as u8, 30 * y as u8, 0));
+        let texel_at = |x: f32| {
+            let hit = hit_top(&cube, x, 0.3);
+            texture.sample(hit.u, hit.v).unwrap()
+        };
+
+        //La cara mide 2: con escala 4 la textura se repite cada 0.5
+        let first = texel_at(-0.9);
+        for repeat in 1..4 {
+            assert_eq!(texel_at(-0.9 + 0.5 * repeat as f32), first);
         }
+        assert_ne!(texel_at(-0.65), first);
     }
-}
\ No newline at end of file
+}
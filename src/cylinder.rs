@@ -0,0 +1,140 @@
+use nalgebra_glm::Vec3;
+use std::f32::consts::PI;
+use crate::ray_intersect::{RayIntersect, Intersect};
+use crate::material::Material;
+use crate::aabb::Aabb;
+
+// t, normal, (dp/du, dp/dv), u, v
+type CylinderHit = (f32, Vec3, (Vec3, Vec3), f32, f32);
+
+// Cilindro vertical: base es el centro de la tapa inferior
+pub struct Cylinder {
+    pub base: Vec3,
+    pub radius: f32,
+    pub height: f32,
+    pub material: Material,
+    pub enabled: bool,
+}
+
+impl RayIntersect for Cylinder {
+    fn ray_intersect(&self, ray_origin: &Vec3, ray_direction: &Vec3) -> Intersect {
+        const EPSILON: f32 = 1e-6;
+
+        // Coordenadas locales respecto a la base
+        let origin = ray_origin - self.base;
+        let mut closest: Option<CylinderHit> = None;
+
+        // Pared: cilindro infinito x^2 + z^2 = r^2 recortado a la altura
+        let a = ray_direction.x * ray_direction.x + ray_direction.z * ray_direction.z;
+        if a > EPSILON {
+            let b = 2.0 * (origin.x * ray_direction.x + origin.z * ray_direction.z);
+            let c = origin.x * origin.x + origin.z * origin.z - self.radius * self.radius;
+            let discriminant = b * b - 4.0 * a * c;
+
+            if discriminant >= 0.0 {
+                let sqrt_discriminant = discriminant.sqrt();
+                for t in [(-b - sqrt_discriminant) / (2.0 * a), (-b + sqrt_discriminant) / (2.0 * a)] {
+                    let y = origin.y + ray_direction.y * t;
+                    if t > EPSILON && (0.0..=self.height).contains(&y) {
+                        let point = origin + ray_direction * t;
+                        let normal = Vec3::new(point.x, 0.0, point.z).normalize();
+                        let u = point.z.atan2(point.x) / (2.0 * PI) + 0.5;
+                        let v = y / self.height;
+                        //u crece con el ángulo (una vuelta completa), v hacia arriba
+                        let dp_du = Vec3::new(-point.z, 0.0, point.x) * (2.0 * PI);
+                        let dp_dv = Vec3::new(0.0, self.height, 0.0);
+                        closest = Some((t, normal, (dp_du, dp_dv), u, v));
+                        break;
+                    }
+                }
+            }
+        }
+
+        // Tapas inferior y superior
+        if ray_direction.y.abs() > EPSILON {
+            for (cap_y, normal_y) in [(0.0, -1.0), (self.height, 1.0)] {
+                let t = (cap_y - origin.y) / ray_direction.y;
+                if t <= EPSILON || closest.is_some_and(|(closest_t, ..)| t >= closest_t) {
+                    continue;
+                }
+                let point = origin + ray_direction * t;
+                if point.x * point.x + point.z * point.z <= self.radius * self.radius {
+                    let u = point.x / (2.0 * self.radius) + 0.5;
+                    let v = point.z / (2.0 * self.radius) + 0.5;
+                    let diameter = 2.0 * self.radius;
+                    let derivatives = (Vec3::new(diameter, 0.0, 0.0), Vec3::new(0.0, 0.0, diameter));
+                    closest = Some((t, Vec3::new(0.0, normal_y, 0.0), derivatives, u, v));
+                }
+            }
+        }
+
+        match closest {
+            Some((t, normal, (dp_du, dp_dv), u, v)) => {
+                let intersection_point = ray_origin + ray_direction * t;
+                let (u, v) = self.material.resolve_uv(u, v);
+                Intersect::new(intersection_point, normal, t, self.material.clone(), u, v)
+                    .with_uv_derivatives(dp_du, dp_dv)
+            }
+            None => Intersect::empty(), // No intersección
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn update(&mut self, time: f32) {
+        self.material.update(time);
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let min = self.base - Vec3::new(self.radius, 0.0, self.radius);
+        let max = self.base + Vec3::new(self.radius, self.height, self.radius);
+        Some(Aabb { min, max })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cylinder() -> Cylinder {
+        Cylinder { base: Vec3::zeros(), radius: 1.0, height: 2.0, material: Material::black(), enabled: true }
+    }
+
+    #[test]
+    fn wall_bitangent_points_up() {
+        let hit = cylinder().ray_intersect(&Vec3::new(5.0, 1.0, 0.0), &Vec3::new(-1.0, 0.0, 0.0));
+        let (tangent, bitangent, _) = hit.tbn();
+        assert!((bitangent - Vec3::new(0.0, 1.0, 0.0)).magnitude() < 1e-5); //v crece hacia arriba
+        assert!((tangent - Vec3::new(0.0, 0.0, 1.0)).magnitude() < 1e-5); //u crece con el ángulo
+    }
+
+    #[test]
+    fn top_cap_bitangent_follows_v() {
+        let hit = cylinder().ray_intersect(&Vec3::new(0.2, 5.0, 0.1), &Vec3::new(0.0, -1.0, 0.0));
+        let (tangent, bitangent, _) = hit.tbn();
+        assert!((tangent - Vec3::new(1.0, 0.0, 0.0)).magnitude() < 1e-5);
+        assert!((bitangent - Vec3::new(0.0, 0.0, 1.0)).magnitude() < 1e-5);
+    }
+
+    #[test]
+    fn side_and_caps_have_their_point_and_normal() {
+        let cylinder = Cylinder { base: Vec3::new(1.0, -1.0, 2.0), ..cylinder() };
+        let side = cylinder.ray_intersect(&Vec3::new(5.0, 0.0, 2.0), &Vec3::new(-1.0, 0.0, 0.0));
+        assert!((side.point - Vec3::new(2.0, 0.0, 2.0)).magnitude() < 1e-5);
+        assert!((side.normal - Vec3::new(1.0, 0.0, 0.0)).magnitude() < 1e-5);
+        assert!((side.distance - 3.0).abs() < 1e-5);
+
+        let top = cylinder.ray_intersect(&Vec3::new(1.3, 5.0, 2.0), &Vec3::new(0.0, -1.0, 0.0));
+        assert!((top.point - Vec3::new(1.3, 1.0, 2.0)).magnitude() < 1e-5);
+        assert!((top.normal - Vec3::new(0.0, 1.0, 0.0)).magnitude() < 1e-5);
+
+        let bottom = cylinder.ray_intersect(&Vec3::new(1.0, -5.0, 1.6), &Vec3::new(0.0, 1.0, 0.0));
+        assert!((bottom.point - Vec3::new(1.0, -1.0, 1.6)).magnitude() < 1e-5);
+        assert!((bottom.normal - Vec3::new(0.0, -1.0, 0.0)).magnitude() < 1e-5);
+
+        //Por encima de la tapa no choca
+        assert!(!cylinder.ray_intersect(&Vec3::new(5.0, 1.5, 2.0), &Vec3::new(-1.0, 0.0, 0.0)).is_intersecting);
+    }
+}
@@ -0,0 +1,108 @@
+use nalgebra_glm::Vec3;
+use std::f32::consts::PI;
+use crate::ray_intersect::{RayIntersect, Intersect};
+use crate::material::Material;
+use crate::aabb::Aabb;
+use crate::sampling::orthonormal_basis;
+
+// Disco plano de dos caras (hojas sobre el lago, superficies redondas)
+pub struct Disk {
+    pub center: Vec3,
+    pub normal: Vec3,
+    pub radius: f32,
+    pub material: Material,
+    pub enabled: bool,
+}
+
+impl RayIntersect for Disk {
+    fn ray_intersect(&self, ray_origin: &Vec3, ray_direction: &Vec3) -> Intersect {
+        const EPSILON: f32 = 1e-6;
+
+        // Plano del disco
+        let normal = self.normal.normalize();
+        let denominator = normal.dot(ray_direction);
+        if denominator.abs() < EPSILON {
+            return Intersect::empty(); // Rayo paralelo
+        }
+        let t = (self.center - ray_origin).dot(&normal) / denominator;
+        if t < EPSILON {
+            return Intersect::empty(); // Detrás del origen
+        }
+
+        // Dentro del radio
+        let intersection_point = ray_origin + ray_direction * t;
+        let offset = intersection_point - self.center;
+        if offset.magnitude_squared() > self.radius * self.radius {
+            return Intersect::empty();
+        }
+
+        // Uvs polares: u = ángulo, v = distancia al centro
+        let (tangent, bitangent) = orthonormal_basis(&normal);
+        let u = offset.dot(&bitangent).atan2(offset.dot(&tangent)) / (2.0 * PI) + 0.5;
+        let v = offset.magnitude() / self.radius;
+        let (u, v) = self.material.resolve_uv(u, v);
+
+        // u gira alrededor del centro (una vuelta completa) y v se aleja de él
+        let dp_du = normal.cross(&offset) * (2.0 * PI);
+        let dp_dv = offset.try_normalize(1e-6).unwrap_or(tangent) * self.radius;
+
+        // La cara que mira al rayo
+        let facing_normal = if denominator > 0.0 { -normal } else { normal };
+        Intersect::new(intersection_point, facing_normal, t, self.material.clone(), u, v)
+            .with_uv_derivatives(dp_du, dp_dv)
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn translate(&mut self, offset: &Vec3) {
+        self.center += offset;
+    }
+
+    fn update(&mut self, time: f32) {
+        self.material.update(time);
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        // Extensión del disco en cada eje: radio * sqrt(1 - n_i^2)
+        let normal = self.normal.normalize();
+        let half_extents = normal.map(|n| self.radius * (1.0 - n * n).max(0.0).sqrt());
+        Some(Aabb::from_center(&self.center, &half_extents))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pad() -> Disk {
+        Disk {
+            center: Vec3::new(1.0, 0.5, -1.0),
+            normal: Vec3::new(0.0, 2.0, 0.0), //se normaliza al trazar
+            radius: 0.5,
+            material: Material::black(),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn hits_inside_the_radius_and_misses_outside() {
+        let pad = pad();
+        let down = Vec3::new(0.0, -1.0, 0.0);
+        let hit = pad.ray_intersect(&Vec3::new(1.3, 3.0, -1.2), &down);
+        assert!(hit.is_intersecting);
+        assert!((hit.point - Vec3::new(1.3, 0.5, -1.2)).magnitude() < 1e-5);
+        assert!((hit.normal - Vec3::new(0.0, 1.0, 0.0)).magnitude() < 1e-5);
+        assert!((hit.v - 0.2_f32.hypot(0.3) / 0.5).abs() < 1e-5);
+
+        assert!(!pad.ray_intersect(&Vec3::new(1.51, 3.0, -1.0), &down).is_intersecting);
+    }
+
+    #[test]
+    fn normal_faces_the_ray_from_below() {
+        let hit = pad().ray_intersect(&Vec3::new(1.0, -2.0, -1.0), &Vec3::new(0.0, 1.0, 0.0));
+        assert!((hit.point - Vec3::new(1.0, 0.5, -1.0)).magnitude() < 1e-5);
+        assert!((hit.normal - Vec3::new(0.0, -1.0, 0.0)).magnitude() < 1e-5);
+    }
+}
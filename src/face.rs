@@ -0,0 +1,38 @@
+use nalgebra_glm::Vec3;
+
+// Cara de una caja alineada a los ejes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Face {
+    PositiveX, // Cara derecha
+    NegativeX, // Cara izquierda
+    PositiveY, // Cara superior
+    NegativeY, // Cara inferior
+    PositiveZ, // Cara frontal
+    NegativeZ, // Cara trasera
+}
+
+impl Face {
+    pub fn normal(self) -> Vec3 {
+        match self {
+            Face::PositiveX => Vec3::new(1.0, 0.0, 0.0),
+            Face::NegativeX => Vec3::new(-1.0, 0.0, 0.0),
+            Face::PositiveY => Vec3::new(0.0, 1.0, 0.0),
+            Face::NegativeY => Vec3::new(0.0, -1.0, 0.0),
+            Face::PositiveZ => Vec3::new(0.0, 0.0, 1.0),
+            Face::NegativeZ => Vec3::new(0.0, 0.0, -1.0),
+        }
+    }
+
+    // Direcciones en que crecen u y v según face_uv
+    pub fn uv_axes(self) -> (Vec3, Vec3) {
+        let (x, y, z) = (Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        match self {
+            Face::PositiveX => (-z, y),
+            Face::NegativeX => (z, y),
+            Face::PositiveY => (x, z),
+            Face::NegativeY => (x, -z),
+            Face::PositiveZ => (x, y),
+            Face::NegativeZ => (-x, y),
+        }
+    }
+}
@@ -1,32 +1,137 @@
+use nalgebra_glm::Vec3;
+use crate::color::Color;
+use crate::tone_mapping::{apply_gamma, ToneMapping};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+#[derive(Debug, PartialEq)]
+pub enum FramebufferError {
+    ZeroDimension { width: usize, height: usize },
+    Overflow { width: usize, height: usize },
+}
+
+impl fmt::Display for FramebufferError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FramebufferError::ZeroDimension { width, height } => write!(f, "Dimensiones invalidas: {}x{}", width, height),
+            FramebufferError::Overflow { width, height } => write!(f, "Framebuffer demasiado grande: {}x{}", width, height),
+        }
+    }
+}
+
+// Dimensiones internas para una escala de render (al menos 1x1)
+pub fn scaled_size(width: usize, height: usize, scale: f32) -> (usize, usize) {
+    let scale_dimension = |dimension: usize| ((dimension as f32 * scale).round() as usize).max(1);
+    (scale_dimension(width), scale_dimension(height))
+}
+
+// Valor de object_ids donde no hay ningún objeto
+pub const NO_OBJECT: u32 = u32::MAX;
 
 pub struct Framebuffer {
     pub width: usize,
     pub height: usize,
-    pub buffer: Vec<u32>,
+    pub buffer: Vec<u32>, //0xAARRGGBB, minifb ignora el alfa
+    pub depth: Vec<f32>,
+    pub hdr: Vec<Vec3>,
+    pub object_ids: Vec<u32>, //índice en la escena del objeto visible, o NO_OBJECT
     background_color: u32,
     current_color: u32,
+    current_depth: f32,
+    current_hdr: Vec3,
+    current_object_id: u32,
 }
 
 impl Framebuffer {
     pub fn new(width: usize, height: usize) -> Self {
-        Framebuffer {
+        Self::try_new(width, height).unwrap()
+    }
+
+    pub fn try_new(width: usize, height: usize) -> Result<Self, FramebufferError> {
+        if width == 0 || height == 0 {
+            return Err(FramebufferError::ZeroDimension { width, height });
+        }
+        // El buffer HDR es el más grande por pixel
+        let pixels = width.checked_mul(height)
+            .filter(|pixels| pixels.checked_mul(std::mem::size_of::<Vec3>()).is_some_and(|bytes| bytes <= isize::MAX as usize))
+            .ok_or(FramebufferError::Overflow { width, height })?;
+
+        Ok(Framebuffer {
             width,
             height,
-            buffer: vec![0; width * height],
+            buffer: vec![0; pixels],
+            depth: vec![f32::INFINITY; pixels],
+            hdr: vec![Vec3::zeros(); pixels],
+            object_ids: vec![NO_OBJECT; pixels],
             background_color: 0x000000,
-            current_color: 0xFFFFFF
+            current_color: 0xFFFFFF,
+            current_depth: f32::INFINITY,
+            current_hdr: Vec3::zeros(),
+            current_object_id: NO_OBJECT,
+        })
+    }
+
+    //Nuevo tamaño; el contenido se pierde. Si las dimensiones no son válidas no cambia nada
+    pub fn resize(&mut self, width: usize, height: usize) -> Result<(), FramebufferError> {
+        if width == self.width && height == self.height {
+            return Ok(());
         }
+        let mut resized = Self::try_new(width, height)?;
+        resized.background_color = self.background_color;
+        *self = resized;
+        Ok(())
     }
 
     pub fn clear(&mut self) {
         for pixel in self.buffer.iter_mut() {
             *pixel = self.background_color;
         }
+        for depth in self.depth.iter_mut() {
+            *depth = f32::INFINITY;
+        }
+        for color in self.hdr.iter_mut() {
+            *color = Vec3::zeros();
+        }
+        for id in self.object_ids.iter_mut() {
+            *id = NO_OBJECT;
+        }
     }
 
     pub fn point(&mut self, x: usize, y: usize) {
         if x < self.width && y < self.height {
             self.buffer[y * self.width + x] = self.current_color;
+            self.depth[y * self.width + x] = self.current_depth;
+            self.hdr[y * self.width + x] = self.current_hdr;
+            self.object_ids[y * self.width + x] = self.current_object_id;
+        }
+    }
+
+    //Bresenham: solo pinta el color (sirve como capa encima del render)
+    pub fn draw_line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32) {
+        let dx = (x1 - x0).abs();
+        let dy = -(y1 - y0).abs();
+        let step_x = if x0 < x1 { 1 } else { -1 };
+        let step_y = if y0 < y1 { 1 } else { -1 };
+        let mut error = dx + dy;
+        let (mut x, mut y) = (x0, y0);
+
+        loop {
+            if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+                self.buffer[y as usize * self.width + x as usize] = self.current_color;
+            }
+            if x == x1 && y == y1 {
+                break;
+            }
+            let doubled = 2 * error;
+            if doubled >= dy {
+                error += dy;
+                x += step_x;
+            }
+            if doubled <= dx {
+                error += dx;
+                y += step_y;
+            }
         }
     }
 
@@ -37,4 +142,217 @@ impl Framebuffer {
     pub fn set_current_color(&mut self, color: u32) {
         self.current_color = color;
     }
-}
\ No newline at end of file
+
+    pub fn set_current_depth(&mut self, depth: f32) {
+        self.current_depth = depth;
+    }
+
+    pub fn set_current_hdr(&mut self, color: Vec3) {
+        self.current_hdr = color;
+    }
+
+    pub fn set_current_object_id(&mut self, id: Option<usize>) {
+        self.current_object_id = id.map_or(NO_OBJECT, |id| id as u32);
+    }
+
+    //Recalcular los colores a partir del buffer HDR
+    pub fn tone_map(&mut self, tone_mapping: ToneMapping, gamma: f32) {
+        for (pixel, color) in self.buffer.iter_mut().zip(self.hdr.iter()) {
+            let alpha = (*pixel >> 24) as u8; //se conserva la cobertura
+            let mapped = apply_gamma(&tone_mapping.apply(color), gamma);
+            *pixel = Color::from_vec3(&mapped).to_hex_with_alpha(alpha);
+        }
+    }
+
+    //Valores en [0, 1] como escala de grises (vistas de depuración)
+    pub fn show_grayscale(&mut self, values: &[f32]) {
+        for (pixel, value) in self.buffer.iter_mut().zip(values.iter()) {
+            let level = (value.clamp(0.0, 1.0) * 255.0) as u32;
+            *pixel = 0xFF000000 | (level << 16) | (level << 8) | level;
+        }
+    }
+
+    //Cuadro actual como bytes RGB fila por fila (p. ej. para ffmpeg -f rawvideo -pix_fmt rgb24)
+    pub fn to_rgb_bytes(&self) -> Vec<u8> {
+        self.buffer.iter()
+            .flat_map(|pixel| [(pixel >> 16) as u8, (pixel >> 8) as u8, *pixel as u8])
+            .collect()
+    }
+
+    //Igual que to_rgb_bytes con el alfa al final (rgba)
+    pub fn to_rgba_bytes(&self) -> Vec<u8> {
+        self.buffer.iter()
+            .flat_map(|pixel| [(pixel >> 16) as u8, (pixel >> 8) as u8, *pixel as u8, (pixel >> 24) as u8])
+            .collect()
+    }
+
+    //RGBA: los pixeles de fondo quedan transparentes
+    pub fn save_png(&self, path: &str) -> image::ImageResult<()> {
+        image::save_buffer(path, &self.to_rgba_bytes(), self.width as u32, self.height as u32, image::ColorType::Rgba8)
+    }
+
+    //Profundidad en escala de grises: cerca = blanco, lejos y fondo = negro
+    pub fn save_depth_png(&self, path: &str) -> image::ImageResult<()> {
+        let finite = self.depth.iter().copied().filter(|d| d.is_finite());
+        let min = finite.clone().fold(f32::INFINITY, f32::min);
+        let max = finite.fold(f32::NEG_INFINITY, f32::max);
+        let range = (max - min).max(f32::EPSILON);
+
+        let pixels: Vec<u8> = self.depth.iter().map(|&d| {
+            if d.is_finite() {
+                (255.0 * (1.0 - (d - min) / range)).round() as u8
+            } else {
+                0
+            }
+        }).collect();
+
+        image::save_buffer(path, &pixels, self.width as u32, self.height as u32, image::ColorType::L8)
+    }
+
+    //PPM binario (P6), sin depender de codificadores de imagen
+    pub fn save_ppm(&self, path: &str) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        writer.write_all(&self.to_rgb_bytes())?;
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scaled_size_scales_each_dimension() {
+        assert_eq!(scaled_size(800, 600, 1.0), (800, 600));
+        assert_eq!(scaled_size(800, 600, 0.5), (400, 300));
+        assert_eq!(scaled_size(801, 601, 0.25), (200, 150)); //redondeado
+        assert_eq!(scaled_size(3, 2, 0.01), (1, 1)); //nunca 0
+        let (width, height) = scaled_size(640, 480, 0.5);
+        let framebuffer = Framebuffer::new(width, height);
+        assert_eq!(framebuffer.buffer.len(), 320 * 240);
+    }
+
+    #[test]
+    fn depth_png_is_white_near_and_black_far() {
+        let mut framebuffer = Framebuffer::new(3, 1);
+        framebuffer.depth.copy_from_slice(&[1.0, 3.0, f32::INFINITY]);
+        let path = std::env::temp_dir().join(format!("diorama_depth_{}.png", std::process::id()));
+        framebuffer.save_depth_png(&path.to_string_lossy()).unwrap();
+        let image = image::open(&path).unwrap().to_luma8();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(image.as_raw(), &vec![255, 0, 0]);
+    }
+
+    #[test]
+    fn try_new_rejects_zero_size() {
+        assert_eq!(Framebuffer::try_new(0, 10).err(), Some(FramebufferError::ZeroDimension { width: 0, height: 10 }));
+        assert_eq!(Framebuffer::try_new(10, 0).err(), Some(FramebufferError::ZeroDimension { width: 10, height: 0 }));
+    }
+
+    #[test]
+    fn try_new_rejects_overflow() {
+        let huge = usize::MAX / 2;
+        assert_eq!(Framebuffer::try_new(huge, 3).err(), Some(FramebufferError::Overflow { width: huge, height: 3 }));
+        // El producto cabe en usize pero no los bytes del buffer HDR
+        let wide = usize::MAX / 8;
+        assert_eq!(Framebuffer::try_new(wide, 1).err(), Some(FramebufferError::Overflow { width: wide, height: 1 }));
+    }
+
+    #[test]
+    fn ppm_reads_back_byte_identical() {
+        let mut framebuffer = Framebuffer::new(5, 3);
+        for (i, pixel) in framebuffer.buffer.iter_mut().enumerate() {
+            *pixel = 0xFF000000 | (i as u32 * 0x0A1B2C);
+        }
+        let path = std::env::temp_dir().join(format!("diorama_test_{}.ppm", std::process::id()));
+        framebuffer.save_ppm(&path.to_string_lossy()).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let header = b"P6\n5 3\n255\n";
+        assert_eq!(&bytes[..header.len()], header);
+        let expected: Vec<u8> = framebuffer.buffer.iter()
+            .flat_map(|pixel| [(pixel >> 16) as u8, (pixel >> 8) as u8, *pixel as u8])
+            .collect();
+        assert_eq!(&bytes[header.len()..], expected.as_slice());
+    }
+
+    #[test]
+    fn rgb_bytes_follow_the_buffer() {
+        let mut framebuffer = Framebuffer::new(4, 3);
+        framebuffer.buffer[5] = 0xFF123456;
+        let bytes = framebuffer.to_rgb_bytes();
+        assert_eq!(bytes.len(), 4 * 3 * 3);
+        assert_eq!(&bytes[15..18], &[0x12, 0x34, 0x56]);
+        assert!(bytes[..15].iter().chain(&bytes[18..]).all(|&byte| byte == 0));
+        let rgba = framebuffer.to_rgba_bytes();
+        assert_eq!(rgba.len(), 4 * 3 * 4);
+        assert_eq!(&rgba[20..24], &[0x12, 0x34, 0x56, 0xFF]);
+    }
+
+    // Pixeles pintados con el color actual, como (x, y)
+    fn painted(framebuffer: &Framebuffer) -> Vec<(usize, usize)> {
+        (0..framebuffer.buffer.len())
+            .filter(|&i| framebuffer.buffer[i] == 0xFFFFFF)
+            .map(|i| (i % framebuffer.width, i / framebuffer.width))
+            .collect()
+    }
+
+    #[test]
+    fn draw_line_covers_each_direction() {
+        let mut framebuffer = Framebuffer::new(8, 8);
+        framebuffer.draw_line(1, 2, 4, 2);
+        assert_eq!(painted(&framebuffer), vec![(1, 2), (2, 2), (3, 2), (4, 2)]);
+
+        framebuffer.clear();
+        framebuffer.draw_line(3, 4, 3, 1); //vertical hacia arriba
+        assert_eq!(painted(&framebuffer), vec![(3, 1), (3, 2), (3, 3), (3, 4)]);
+
+        framebuffer.clear();
+        framebuffer.draw_line(0, 0, 3, 3);
+        assert_eq!(painted(&framebuffer), vec![(0, 0), (1, 1), (2, 2), (3, 3)]);
+
+        //Empinada: un pixel por fila
+        framebuffer.clear();
+        framebuffer.draw_line(1, 0, 2, 5);
+        let steep = painted(&framebuffer);
+        assert_eq!(steep.len(), 6);
+        assert!(steep.iter().enumerate().all(|(row, &(x, y))| y == row && (1..=2).contains(&x)));
+    }
+
+    #[test]
+    fn draw_line_clips_at_the_edges() {
+        let mut framebuffer = Framebuffer::new(4, 3);
+        framebuffer.draw_line(-3, 1, 6, 1);
+        assert_eq!(painted(&framebuffer), vec![(0, 1), (1, 1), (2, 1), (3, 1)]);
+
+        framebuffer.clear();
+        framebuffer.draw_line(10, 10, 20, 20); //todo afuera
+        assert!(painted(&framebuffer).is_empty());
+    }
+
+    #[test]
+    fn resize_reallocates_every_buffer() {
+        let mut framebuffer = Framebuffer::new(4, 3);
+        framebuffer.set_background_color(0x102030);
+        framebuffer.set_current_depth(1.0);
+        framebuffer.set_current_object_id(Some(2));
+        framebuffer.point(1, 1);
+
+        framebuffer.resize(6, 5).unwrap();
+        assert_eq!((framebuffer.width, framebuffer.height), (6, 5));
+        assert_eq!(framebuffer.buffer.len(), 30);
+        assert_eq!(framebuffer.hdr.len(), 30);
+        assert_eq!(framebuffer.depth.len(), 30);
+        assert_eq!(framebuffer.object_ids.len(), 30);
+        assert!(framebuffer.depth.iter().all(|d| d.is_infinite()));
+        assert!(framebuffer.object_ids.iter().all(|&id| id == NO_OBJECT));
+        framebuffer.clear();
+        assert!(framebuffer.buffer.iter().all(|&pixel| pixel == 0x102030)); //se conserva el fondo
+
+        assert!(framebuffer.resize(0, 5).is_err());
+        assert_eq!((framebuffer.width, framebuffer.height), (6, 5));
+    }
+}
@@ -0,0 +1,66 @@
+use nalgebra_glm::Vec3;
+use crate::aabb::Aabb;
+use crate::camera::{Camera, Projection};
+
+// Plano con la normal hacia adentro: dentro si normal·(p - eye) + offset >= 0
+struct Plane {
+    normal: Vec3,
+    offset: f32,
+}
+
+// Volumen visible de la camara, para descartar objetos antes de trazar
+pub struct Frustum {
+    eye: Vec3,
+    planes: Vec<Plane>,
+}
+
+impl Frustum {
+    pub fn from_camera(camera: &Camera, aspect_ratio: f32) -> Self {
+        let forward = camera.forward();
+        let right = camera.right();
+        let up = camera.up();
+
+        let near = Plane { normal: forward, offset: 0.0 };
+        let planes = match camera.projection {
+            // Los lados pasan por el ojo, abiertos según el fov
+            Projection::Perspective => {
+                let scale = (camera.fov() * 0.5).tan();
+                let horizontal = forward * (aspect_ratio * scale);
+                let vertical = forward * scale;
+                vec![
+                    near,
+                    Plane { normal: horizontal - right, offset: 0.0 },
+                    Plane { normal: horizontal + right, offset: 0.0 },
+                    Plane { normal: vertical - up, offset: 0.0 },
+                    Plane { normal: vertical + up, offset: 0.0 },
+                ]
+            }
+            // Caja infinita hacia adelante del tamaño del plano de imagen
+            Projection::Orthographic => {
+                let (half_width, half_height) = camera.ortho_half_extents(aspect_ratio);
+                vec![
+                    near,
+                    Plane { normal: -right, offset: half_width },
+                    Plane { normal: right, offset: half_width },
+                    Plane { normal: -up, offset: half_height },
+                    Plane { normal: up, offset: half_height },
+                ]
+            }
+        };
+
+        Frustum { eye: camera.eye, planes }
+    }
+
+    // Falso solo si la caja queda entera fuera de algún plano
+    pub fn intersects(&self, bounds: &Aabb) -> bool {
+        self.planes.iter().all(|plane| {
+            // Esquina más adentro según la normal
+            let corner = Vec3::new(
+                if plane.normal.x >= 0.0 { bounds.max.x } else { bounds.min.x },
+                if plane.normal.y >= 0.0 { bounds.max.y } else { bounds.min.y },
+                if plane.normal.z >= 0.0 { bounds.max.z } else { bounds.min.z },
+            );
+            plane.normal.dot(&(corner - self.eye)) + plane.offset >= 0.0
+        })
+    }
+}
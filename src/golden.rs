@@ -0,0 +1,79 @@
+// Prueba de regresión: la escena por defecto con semilla fija contra golden/default_scene.png
+// Con DIORAMA_UPDATE_GOLDEN definida la prueba regenera la referencia en vez de comparar:
+// DIORAMA_UPDATE_GOLDEN=1 cargo test golden
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use crate::framebuffer::Framebuffer;
+    use crate::offscreen::OffscreenRenderer;
+    use crate::render_settings::RenderSettings;
+    use crate::{build_scene, default_camera, default_light};
+
+    const GOLDEN_PATH: &str = "golden/default_scene.png";
+    const GOLDEN_WIDTH: usize = 96;
+    const GOLDEN_HEIGHT: usize = 72;
+    const GOLDEN_SEED: u64 = 1;
+    const UPDATE_ENV: &str = "DIORAMA_UPDATE_GOLDEN";
+
+    // Diferencias por canal hasta PIXEL_TOLERANCE no cuentan (ruido de punto flotante)
+    const PIXEL_TOLERANCE: u8 = 8;
+    // Error medio máximo por canal, en niveles de 0 a 255
+    const MAX_MEAN_ERROR: f32 = 0.5;
+
+    // Error medio por canal (RGBA) y cantidad de pixeles distintos
+    fn compare(framebuffer: &Framebuffer, reference: &image::RgbaImage) -> (f32, usize) {
+        let mut total_error = 0.0;
+        let mut differing_pixels = 0;
+        for (pixel, expected) in framebuffer.buffer.iter().zip(reference.pixels()) {
+            let rendered = [(pixel >> 16) as u8, (pixel >> 8) as u8, *pixel as u8, (pixel >> 24) as u8];
+            let mut differs = false;
+            for (a, b) in rendered.iter().zip(expected.0.iter()) {
+                let difference = a.abs_diff(*b);
+                if difference > PIXEL_TOLERANCE {
+                    total_error += difference as f32;
+                    differs = true;
+                }
+            }
+            if differs {
+                differing_pixels += 1;
+            }
+        }
+        (total_error / (framebuffer.buffer.len() * 4) as f32, differing_pixels)
+    }
+
+    #[test]
+    fn default_scene_matches_golden() {
+        let mut settings = RenderSettings::new();
+        settings.seed = GOLDEN_SEED;
+        let mut renderer = OffscreenRenderer::new(
+            GOLDEN_WIDTH,
+            GOLDEN_HEIGHT,
+            build_scene(None, None),
+            default_camera(),
+            default_light(),
+            settings,
+        ).unwrap();
+        renderer.render_frame();
+        let framebuffer = &renderer.framebuffer;
+
+        if std::env::var_os(UPDATE_ENV).is_some() {
+            if let Some(parent) = Path::new(GOLDEN_PATH).parent() {
+                std::fs::create_dir_all(parent).unwrap();
+            }
+            framebuffer.save_png(GOLDEN_PATH).unwrap();
+            return;
+        }
+
+        let reference = image::open(GOLDEN_PATH)
+            .unwrap_or_else(|e| panic!("No se pudo abrir {}: {} (regenerar con {}=1)", GOLDEN_PATH, e, UPDATE_ENV))
+            .to_rgba8();
+        assert_eq!(reference.dimensions(), (GOLDEN_WIDTH as u32, GOLDEN_HEIGHT as u32));
+
+        let (mean_error, differing_pixels) = compare(framebuffer, &reference);
+        assert!(
+            mean_error <= MAX_MEAN_ERROR,
+            "El render difiere de la referencia: error medio {:.3} (máximo {}), {} pixeles distintos",
+            mean_error, MAX_MEAN_ERROR, differing_pixels
+        );
+    }
+}
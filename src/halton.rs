@@ -0,0 +1,42 @@
+// Secuencia de Halton: puntos de baja discrepancia, deterministas y bien repartidos
+// Sirve para jitter de subpixel (y para cualquier muestreo 2D como profundidad de campo)
+
+// Inverso radical de `index` en la base dada, en [0, 1)
+pub fn halton(mut index: u32, base: u32) -> f32 {
+    let mut result = 0.0;
+    let mut fraction = 1.0 / base as f32;
+    while index > 0 {
+        result += (index % base) as f32 * fraction;
+        index /= base;
+        fraction /= base as f32;
+    }
+    result
+}
+
+// Punto 2D con bases 2 y 3; el índice 0 es (0, 0), por eso se empieza en 1
+pub fn halton_2d(index: u32) -> (f32, f32) {
+    (halton(index, 2), halton(index, 3))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_points_match_halton_2_3() {
+        let expected = [
+            (0.5, 1.0 / 3.0),
+            (0.25, 2.0 / 3.0),
+            (0.75, 1.0 / 9.0),
+            (0.125, 4.0 / 9.0),
+            (0.625, 7.0 / 9.0),
+            (0.375, 2.0 / 9.0),
+            (0.875, 5.0 / 9.0),
+        ];
+        for (index, (x, y)) in (1..).zip(expected) {
+            let (hx, hy) = halton_2d(index);
+            assert!((hx - x).abs() < 1e-6 && (hy - y).abs() < 1e-6, "{}: ({}, {})", index, hx, hy);
+        }
+        assert_eq!(halton_2d(0), (0.0, 0.0));
+    }
+}
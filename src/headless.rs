@@ -0,0 +1,166 @@
+use std::f32::consts::PI;
+use std::io::Write;
+use std::path::Path;
+use std::time::Instant;
+use crate::camera::Camera;
+use crate::framebuffer::Framebuffer;
+use crate::light::Light;
+use crate::render;
+use crate::render_settings::RenderSettings;
+use crate::scene::Scene;
+
+// Progreso en porcentaje: solo llama `report` cuando cambia el valor
+pub struct Progress<F: FnMut(usize)> {
+    last_percent: Option<usize>,
+    report: F,
+}
+
+impl<F: FnMut(usize)> Progress<F> {
+    pub fn new(report: F) -> Self {
+        Progress {
+            last_percent: None,
+            report,
+        }
+    }
+
+    pub fn tile_done(&mut self, tiles_done: usize, total_tiles: usize) {
+        let percent = tiles_done * 100 / total_tiles.max(1);
+        if self.last_percent != Some(percent) {
+            self.last_percent = Some(percent);
+            (self.report)(percent);
+        }
+    }
+}
+
+// Render sin ventana; con verbose imprime el avance y el tiempo en stderr
+pub fn render_headless(
+    framebuffer: &mut Framebuffer,
+    scene: &Scene,
+    camera: &Camera,
+    light: &Light,
+    settings: &RenderSettings,
+    verbose: bool,
+) {
+    let start = Instant::now();
+
+    if !verbose {
+        render(framebuffer, scene, camera, light, settings, None);
+        return;
+    }
+
+    let mut progress = Progress::new(|percent| {
+        eprint!("\rRenderizando: {:3}%", percent);
+        if percent == 100 {
+            eprintln!();
+        }
+    });
+    let mut on_tile_done = |done: usize, total: usize| progress.tile_done(done, total);
+    render(framebuffer, scene, camera, light, settings, Some(&mut on_tile_done));
+
+    eprintln!("Tiempo: {:.2?}", start.elapsed());
+}
+
+// Vuelta completa alrededor de camera.center en `frames` cuadros: frame_0000.png, frame_0001.png...
+// Con output_dir "-" los cuadros salen como RGB crudo por stdout (ffmpeg -f rawvideo -pix_fmt rgb24)
+pub fn render_turntable(
+    framebuffer: &mut Framebuffer,
+    scene: &Scene,
+    camera: &mut Camera,
+    light: &Light,
+    settings: &RenderSettings,
+    frames: usize,
+    output_dir: &str,
+) -> image::ImageResult<()> {
+    let to_stdout = output_dir == "-";
+    if !to_stdout {
+        std::fs::create_dir_all(output_dir)?;
+    }
+    let step = 2.0 * PI / frames.max(1) as f32;
+    let mut stdout = std::io::stdout().lock();
+
+    for frame in 0..frames {
+        render(framebuffer, scene, camera, light, settings, None);
+        if to_stdout {
+            stdout.write_all(&framebuffer.to_rgb_bytes())?;
+        } else {
+            let path = Path::new(output_dir).join(format!("frame_{:04}.png", frame));
+            framebuffer.save_png(&path.to_string_lossy())?;
+        }
+        eprint!("\rCuadro {}/{}", frame + 1, frames);
+        camera.orbit(step, 0.0);
+    }
+    eprintln!();
+    stdout.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra_glm::Vec3;
+    use crate::color::Color;
+    use crate::cube::Cube;
+    use crate::material::Material;
+
+    #[test]
+    fn progress_reports_100_exactly_once() {
+        for total in [1, 3, 7, 250] {
+            let mut reported = Vec::new();
+            let mut progress = Progress::new(|percent| reported.push(percent));
+            for done in 1..=total {
+                progress.tile_done(done, total);
+            }
+            assert_eq!(reported.iter().filter(|&&percent| percent == 100).count(), 1);
+            assert!(reported.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+    }
+
+    #[test]
+    fn render_drives_progress_to_100() {
+        let mut framebuffer = Framebuffer::new(20, 20);
+        let mut settings = RenderSettings::new();
+        settings.tile_size = 8; //3x3 tiles, el último incompleto
+        let camera = Camera::new(Vec3::new(0.0, 0.0, 5.0), Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+        let light = Light::new(Vec3::new(0.0, 5.0, 0.0), Color::new(255, 255, 255), 1.0);
+
+        let mut reported = Vec::new();
+        let mut progress = Progress::new(|percent| reported.push(percent));
+        let mut on_tile_done = |done: usize, total: usize| progress.tile_done(done, total);
+        render(&mut framebuffer, &Scene::new(), &camera, &light, &settings, Some(&mut on_tile_done));
+
+        assert_eq!(reported.last(), Some(&100));
+        assert_eq!(reported.iter().filter(|&&percent| percent == 100).count(), 1);
+    }
+
+    #[test]
+    fn turntable_writes_one_numbered_png_per_frame() {
+        let mut scene = Scene::new();
+        scene.add_object(Box::new(Cube {
+            center: Vec3::new(1.0, 0.0, 0.0), //fuera del centro: cada ángulo se ve distinto
+            side_length: 1.0,
+            material: Material::black(),
+            enabled: true,
+        }));
+        let eye = Vec3::new(0.0, 0.0, 5.0);
+        let mut camera = Camera::new(eye, Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+        let light = Light::new(Vec3::new(0.0, 5.0, 0.0), Color::new(255, 255, 255), 1.0);
+        let mut framebuffer = Framebuffer::new(16, 12);
+        let output = std::env::temp_dir().join(format!("diorama_turntable_{}", std::process::id()));
+
+        render_turntable(&mut framebuffer, &scene, &mut camera, &light, &RenderSettings::new(), 8, &output.to_string_lossy()).unwrap();
+        let mut names: Vec<String> = std::fs::read_dir(&output).unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        let frames: Vec<Vec<u8>> = names.iter()
+            .map(|name| image::open(output.join(name)).unwrap().to_rgba8().into_raw())
+            .collect();
+        std::fs::remove_dir_all(&output).unwrap();
+
+        let expected: Vec<String> = (0..8).map(|frame| format!("frame_{:04}.png", frame)).collect();
+        assert_eq!(names, expected);
+        assert_ne!(frames[0], frames[2]);
+        assert_ne!(frames[2], frames[4]);
+        assert!((camera.eye - eye).magnitude() < 1e-3); //vuelta completa
+    }
+}
@@ -1,11 +1,16 @@
-
 use nalgebra_glm::Vec3;
+use std::f32::consts::PI;
 use crate::color::Color;
 
 pub struct Light {
     pub position: Vec3,
     pub color: Color,
     pub intensity: f32,
+    pub direction: Vec3,
+    pub cone_angle: f32, //medio ángulo del cono, PI = luz puntual
+    pub attenuation: Option<f32>, //coeficiente k de la caída 1 / (1 + k d^2), None = sin caída
+    pub radius: f32, //0 = luz puntual, mayor a 0 = esfera (sombras suaves)
+    pub directional: bool, //rayos paralelos según direction (sol lejano), sin posición
 }
 
 impl Light {
@@ -14,6 +19,209 @@ impl Light {
             position,
             color,
             intensity,
+            direction: Vec3::new(0.0, -1.0, 0.0),
+            cone_angle: PI,
+            attenuation: None,
+            radius: 0.0,
+            directional: false,
+        }
+    }
+
+    // Color por temperatura: 2700K cálida, 6500K luz de día
+    pub fn from_kelvin(position: Vec3, kelvin: f32, intensity: f32) -> Self {
+        Light::new(position, Color::from_kelvin(kelvin), intensity)
+    }
+
+    pub fn spot(position: Vec3, direction: Vec3, cone_angle: f32, color: Color, intensity: f32) -> Self {
+        Light {
+            position,
+            color,
+            intensity,
+            direction: direction.normalize(),
+            cone_angle,
+            attenuation: None,
+            radius: 0.0,
+            directional: false,
+        }
+    }
+
+    // Luz paralela: misma dirección e intensidad en todo punto
+    pub fn directional(direction: Vec3, color: Color, intensity: f32) -> Self {
+        let direction = direction.normalize();
+        Light {
+            position: -direction * 1000.0, //solo referencia, no se usa al iluminar
+            color,
+            intensity,
+            direction,
+            cone_angle: PI,
+            attenuation: None,
+            radius: 0.0,
+            directional: true,
         }
     }
-}
\ No newline at end of file
+
+    // Dirección normalizada desde el punto hacia la luz
+    pub fn to_light(&self, point: &Vec3) -> Vec3 {
+        if self.directional {
+            -self.direction.normalize()
+        } else {
+            (self.position - point).normalize()
+        }
+    }
+
+    // Rayo de sombra `index` de `count`: dirección y distancia hasta la luz
+    pub fn shadow_ray(&self, index: u32, count: u32, from: &Vec3, rotation: f32) -> (Vec3, f32) {
+        if self.directional {
+            return (-self.direction.normalize(), f32::INFINITY);
+        }
+        let to_light = self.sample_position(index, count, from, rotation) - from;
+        let distance = to_light.magnitude();
+        (to_light / distance, distance)
+    }
+
+    pub fn translate(&mut self, offset: Vec3) {
+        self.position += offset;
+    }
+
+    // 1 dentro del cono, 0 fuera, con transición suave en el borde
+    pub fn spot_factor(&self, point: &Vec3) -> f32 {
+        if self.cone_angle >= PI || self.directional {
+            return 1.0;
+        }
+
+        let to_point = (point - self.position).normalize();
+        let cos_angle = to_point.dot(&self.direction.normalize());
+        let outer = self.cone_angle.cos();
+        let inner = (self.cone_angle * 0.8).cos();
+
+        let t = ((cos_angle - outer) / (inner - outer)).clamp(0.0, 1.0);
+        t * t * (3.0 - 2.0 * t)
+    }
+
+    // Caída con la distancia: 1 / (1 + k d^2); con k d^2 grande, al doble de distancia
+    // llega un cuarto
+    pub fn attenuation_at(&self, point: &Vec3) -> f32 {
+        let Some(coefficient) = self.attenuation else {
+            return 1.0;
+        };
+        if self.directional {
+            return 1.0;
+        }
+        1.0 / (1.0 + coefficient * self.falloff_distance_squared(point))
+    }
+
+    // d^2 equivalente de una esfera de radio r: pi r^2 / ángulo sólido que cubre vista
+    // desde el punto. Ese ángulo es 2 pi (1 - cos t) con sin t = r / d, lo que queda en
+    // max(d, r)^2 (1 + cos t) / 2: d^2 cuando r -> 0 y r^2 / 2 dentro de la esfera
+    fn falloff_distance_squared(&self, point: &Vec3) -> f32 {
+        let distance_squared = (point - self.position).magnitude_squared();
+        let radius_squared = self.radius * self.radius;
+        let sin_squared = (radius_squared / distance_squared.max(f32::MIN_POSITIVE)).min(1.0);
+        let cos = (1.0 - sin_squared).sqrt();
+        distance_squared.max(radius_squared) * (1.0 + cos) / 2.0
+    }
+
+    // Luz que llega a `point` (sin sombras): dirección hacia la luz, color y
+    // cuánto llega (intensidad, cono y distancia)
+    pub fn radiance_at(&self, point: &Vec3) -> (Vec3, Vec3, f32) {
+        let amount = self.intensity * self.spot_factor(point) * self.attenuation_at(point);
+        (self.to_light(point), self.color.to_vec3(), amount)
+    }
+
+    // Punto `index` de `count` sobre el disco de la esfera visto desde `from`
+    // `rotation` en [0, 1) gira la espiral
+    pub fn sample_position(&self, index: u32, count: u32, from: &Vec3, rotation: f32) -> Vec3 {
+        if self.radius <= 0.0 || count <= 1 {
+            return self.position;
+        }
+
+        let axis = (self.position - from).normalize();
+        let helper = if axis.x.abs() > 0.9 { Vec3::new(0.0, 1.0, 0.0) } else { Vec3::new(1.0, 0.0, 0.0) };
+        let tangent = axis.cross(&helper).normalize();
+        let bitangent = axis.cross(&tangent);
+
+        // Espiral de Fibonacci sobre el disco
+        let golden_angle = PI * (3.0 - 5.0_f32.sqrt());
+        let r = self.radius * ((index as f32 + 0.5) / count as f32).sqrt();
+        let phi = index as f32 * golden_angle + rotation * 2.0 * PI;
+
+        self.position + tangent * (r * phi.cos()) + bitangent * (r * phi.sin())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spot_lights_only_inside_its_cone() {
+        let light = Light::spot(Vec3::new(0.0, 5.0, 0.0), Vec3::new(0.0, -1.0, 0.0), PI / 8.0, Color::new(255, 255, 255), 1.0);
+        let (_, _, inside) = light.radiance_at(&Vec3::new(0.3, 0.0, 0.0));
+        let (_, _, outside) = light.radiance_at(&Vec3::new(4.0, 0.0, 0.0));
+        assert!((inside - 1.0).abs() < 1e-5);
+        assert_eq!(outside, 0.0);
+    }
+
+    #[test]
+    fn daylight_is_neutral_and_tungsten_is_warmer() {
+        let daylight = Light::from_kelvin(Vec3::zeros(), 6500.0, 1.0).color.to_vec3();
+        let warm = Light::from_kelvin(Vec3::zeros(), 2700.0, 1.0).color.to_vec3();
+        assert!(daylight.min() > 0.95, "{:?}", daylight);
+        assert!(warm.x / warm.z > daylight.x / daylight.z);
+    }
+
+    #[test]
+    fn directional_light_is_the_same_everywhere() {
+        let light = Light::directional(Vec3::new(-1.0, -2.0, -0.5), Color::new(255, 255, 255), 1.0);
+        let normal = Vec3::new(0.0, 1.0, 0.0);
+        let diffuse = |point: Vec3| {
+            let (to_light, _, amount) = light.radiance_at(&point);
+            normal.dot(&to_light).max(0.0) * amount
+        };
+        let near = diffuse(Vec3::zeros());
+        assert!(near > 0.0);
+        assert_eq!(near, diffuse(Vec3::new(40.0, -3.0, -25.0)));
+    }
+
+    #[test]
+    fn twice_as_far_gets_a_quarter_of_the_light() {
+        let mut light = Light::new(Vec3::zeros(), Color::new(255, 255, 255), 1.0);
+        light.attenuation = Some(100.0);
+        let near = light.attenuation_at(&Vec3::new(1.5, 0.0, 0.0));
+        let far = light.attenuation_at(&Vec3::new(3.0, 0.0, 0.0));
+        assert!((far / near - 0.25).abs() < 1e-2);
+    }
+
+    #[test]
+    fn no_coefficient_keeps_full_intensity() {
+        let light = Light::new(Vec3::zeros(), Color::new(255, 255, 255), 1.0);
+        for distance in [0.0, 1.0, 50.0] {
+            assert_eq!(light.attenuation_at(&Vec3::new(distance, 0.0, 0.0)), 1.0);
+        }
+    }
+
+    #[test]
+    fn attenuation_is_capped_inside_the_radius() {
+        let mut light = Light::new(Vec3::zeros(), Color::new(255, 255, 255), 1.0);
+        light.attenuation = Some(1.0);
+        light.radius = 0.5;
+        let inside = light.attenuation_at(&Vec3::new(0.1, 0.0, 0.0));
+        let surface = light.attenuation_at(&Vec3::new(0.5, 0.0, 0.0));
+        assert!((inside - surface).abs() < 1e-5);
+    }
+
+    #[test]
+    fn large_sphere_falls_off_slower_near_its_surface() {
+        // Cerca de una esfera grande el ángulo sólido es mayor que el de un punto
+        let mut point = Light::new(Vec3::zeros(), Color::new(255, 255, 255), 1.0);
+        point.attenuation = Some(1.0);
+        let mut sphere = Light::new(Vec3::zeros(), Color::new(255, 255, 255), 1.0);
+        sphere.attenuation = Some(1.0);
+        sphere.radius = 1.0;
+
+        let near = Vec3::new(1.2, 0.0, 0.0);
+        assert!(sphere.attenuation_at(&near) > point.attenuation_at(&near));
+        let far = Vec3::new(100.0, 0.0, 0.0);
+        assert!((sphere.attenuation_at(&far) / point.attenuation_at(&far) - 1.0).abs() < 1e-3);
+    }
+}
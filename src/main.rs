@@ -1,130 +1,424 @@
 mod framebuffer;
 mod ray_intersect;
+mod aabb;
 mod cube;
 mod rectangular_prism;
+mod face;
+mod triangle;
+mod cylinder;
+mod triangular_prism;
+mod disk;
+mod ripples;
+mod mesh;
 mod color;
 mod camera;
 mod light;
 mod material;
+mod material_library;
+mod ambient_occlusion;
+mod sampling;
+mod scene;
+mod frustum;
+mod tone_mapping;
+mod render_settings;
+mod postprocess;
+mod background;
+mod headless;
+mod offscreen;
+mod golden;
+mod bench;
+mod accumulation;
+mod cli;
+mod halton;
+mod rng;
+mod toml_lite;
+mod view_config;
 
-use minifb::{Key, KeyRepeat, Window, WindowOptions};
+use minifb::{Key, KeyRepeat, MouseButton, MouseMode, ScaleMode, Window, WindowOptions};
 use nalgebra_glm::{Vec3, normalize};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::f32::consts::PI;
 
 use crate::color::Color;
-use crate::ray_intersect::{Intersect, RayIntersect};
 use crate::cube::Cube;
 use crate::rectangular_prism::RectangularPrism;
-use crate::framebuffer::Framebuffer;
-use crate::camera::Camera;
+use crate::mesh::Mesh;
+use crate::cylinder::Cylinder;
+use crate::triangular_prism::TriangularPrism;
+use crate::disk::Disk;
+use crate::ripples::Ripples;
+use crate::framebuffer::{scaled_size, Framebuffer};
+use crate::camera::{drag_to_orbit, drag_to_pan, next_preset, Camera, CameraPreset, Projection};
 use crate::light::Light;
-use crate::material::Material;
+use crate::material::{Material, MaterialBuilder, Texture, TextureCache, WrapMode};
+use std::sync::Arc;
+use crate::ambient_occlusion::AmbientOcclusion;
+use crate::scene::Scene;
+use crate::frustum::Frustum;
+use crate::render_settings::{DebugView, RenderSettings};
+use crate::postprocess::{depth_edges, Bloom};
+use crate::tone_mapping::apply_gamma;
+use crate::background::Background;
+use crate::headless::{render_headless, render_turntable};
+use crate::offscreen::OffscreenRenderer;
+use crate::bench::run_bench;
+use crate::accumulation::Accumulator;
+use crate::cli::{BuiltinScene, CliOptions};
+use crate::halton::halton_2d;
+use crate::rng::Rng;
+use crate::sampling::{cosine_weighted_hemisphere, spiral_2d};
+use crate::view_config::{ViewConfig, VIEW_CONFIG_PATH};
+use crate::material_library::MaterialLibrary;
 
 fn reflect(incident: &Vec3, normal: &Vec3) -> Vec3 {
     incident - 2.0 * incident.dot(normal) * normal
 }
 
-pub fn cast_ray(ray_origin: &Vec3, ray_direction: &Vec3, cubes: &[Cube], rectangles: &[RectangularPrism], light: &Light) -> Color {
-    let mut intersect = Intersect::empty();
-    let mut zbuffer = f32::INFINITY;
+// Fracción de la luz visible desde el punto (1 = sin sombra)
+fn shadow_visibility(point: &Vec3, normal: &Vec3, scene: &Scene, light: &Light, samples: u32, rotation: f32) -> f32 {
+    let samples = if light.radius > 0.0 && !light.directional { samples.max(1) } else { 1 };
+    //muy poco sesgo: la superficie se sombrea a sí misma; demasiado: la sombra se despega
+    let origin = point + normal * scene.shadow_bias;
 
-    // Combina la intersección de cubos y prismas rectangulares
-    let objects = cubes.iter().map(|obj| obj as &dyn RayIntersect)
-        .chain(rectangles.iter().map(|obj| obj as &dyn RayIntersect));
+    let mut visible = 0;
+    for i in 0..samples {
+        let (direction, distance) = light.shadow_ray(i, samples, &origin, rotation);
 
-    for object in objects {
-        let tmp = object.ray_intersect(ray_origin, ray_direction);
-        if tmp.is_intersecting {
-            // Early exit si la distancia es mayor que el zbuffer
-            if tmp.distance < zbuffer {
-                zbuffer = tmp.distance;
-                intersect = tmp;
-            } else {
-                continue;
-            }
+        let hit = scene.nearest_hit(&origin, &direction);
+        if !hit.is_intersecting || hit.distance > distance {
+            visible += 1;
         }
     }
 
+    visible as f32 / samples as f32
+}
+
+// Devuelve el color HDR y la distancia del impacto más cercano (infinito si no hay)
+// `candidates` limita el primer impacto a esos objetos (los visibles para rayos primarios)
+pub fn cast_ray(
+    ray_origin: &Vec3,
+    ray_direction: &Vec3,
+    scene: &Scene,
+    light: &Light,
+    settings: &RenderSettings,
+    depth: u32,
+    candidates: Option<&[usize]>,
+) -> (Vec3, f32) {
+    let mut intersect = match candidates {
+        Some(indices) => scene.nearest_hit_among(indices, ray_origin, ray_direction),
+        None => scene.nearest_hit(ray_origin, ray_direction),
+    };
+
     if !intersect.is_intersecting {
-        return Color::new(9, 20, 55); // Color de fondo
+        return (scene.background.sample(ray_direction), f32::INFINITY); // Color de fondo
     }
 
-    let light_dir = (light.position - intersect.point).normalize();
+    // Mapa de normales en espacio tangente
+    if let Some(texel) = intersect.material.normal_map.as_ref().and_then(|map| map.sample(intersect.u, intersect.v)) {
+        let to_unit = |c: u8| ((c as f32 - 128.0) / 127.0).clamp(-1.0, 1.0);
+        let (tangent, bitangent, normal) = intersect.tbn();
+
+        intersect.normal = (tangent * to_unit(texel[0])
+            + bitangent * to_unit(texel[1])
+            + normal * to_unit(texel[2])).normalize();
+    }
+
+    // Olas animadas del agua
+    if let Some(ripples) = intersect.material.ripples.as_ref() {
+        let (tangent, bitangent, normal) = intersect.tbn();
+        intersect.normal = ripples.perturb(&tangent, &bitangent, &normal, intersect.u, intersect.v);
+    }
+
+    // Mismo punto y misma semilla dan las mismas muestras
+    let mut rng = Rng::for_point(settings.seed, &intersect.point);
+
+    let (light_dir, light_color, mut light_intensity) = light.radiance_at(&intersect.point);
+    if light_intensity > 0.0 {
+        light_intensity *= shadow_visibility(&intersect.point, &intersect.normal, scene, light, settings.shadow_samples, rng.next_f32());
+    }
     let view_dir = (ray_origin - intersect.point).normalize();
     let reflect_dir = reflect(&-light_dir, &intersect.normal);
 
-    let diffuse_intensity = intersect.normal.dot(&light_dir).max(0.0).min(1.0);
-    let mut diffuse = intersect.material.diffuse * intersect.material.albedo[0] * diffuse_intensity * light.intensity;
+    // Todo el sombreado en flotante: los colores u8 solo se convierten al escribir el pixel
+    let diffuse_intensity = intersect.normal.dot(&light_dir).clamp(0.0, 1.0);
+    let mut base_color = intersect.material.diffuse.to_vec3();
 
     // Manejo de texturas
-    if let Some(texture) = intersect.material.texture.as_ref() {
-        let u = intersect.u; 
-        let v = intersect.v; 
-        let texture_width = texture.width;
-        let texture_height = texture.height;
+    if let Some(pixel_color) = intersect.material.texture.as_ref().and_then(|texture| texture.sample(intersect.u, intersect.v)) {
+        base_color += Color::new(pixel_color[0], pixel_color[1], pixel_color[2]).to_vec3();
+    }
+    let mut diffuse = base_color * (intersect.material.albedo[0] * diffuse_intensity * light_intensity);
 
-        let texture_x = (u * texture_width as f32).clamp(0.0, (texture_width - 1) as f32) as usize;
-        let texture_y = (v * texture_height as f32).clamp(0.0, (texture_height - 1) as f32) as usize;
-        let texture_index = (texture_y * texture_width + texture_x) * 4;
+    // Luz ambiental de la escena, llega también a caras en sombra
+    diffuse += scene.ambient.to_vec3() * intersect.material.albedo[0];
 
-        let pixel_color = &texture.data[texture_index..texture_index + 4];
-        let tex_color = Color::new(pixel_color[0], pixel_color[1], pixel_color[2]);
+    // Oclusión ambiental: oscurece esquinas y huecos
+    if let Some(ao) = settings.ambient_occlusion.as_ref() {
+        let occlusion = ao.occlusion(&intersect.point, &intersect.normal, rng.next_f32(), |origin, direction| {
+            let hit = scene.nearest_hit(origin, direction);
+            if hit.is_intersecting { Some(hit.distance) } else { None }
+        });
+        diffuse *= 1.0 - occlusion;
+    }
 
-        diffuse += tex_color * intersect.material.albedo[0] * diffuse_intensity * light.intensity;
+    // Sin brillo en caras que no miran a la luz ni en materiales mate (albedo[1] == 0)
+    let specular_intensity = if diffuse_intensity > 0.0 && intersect.material.albedo[1] > 0.0 {
+        view_dir.dot(&reflect_dir).max(0.0).powf(intersect.material.specular)
+    } else {
+        0.0
+    };
+    let specular = light_color.component_mul(&intersect.material.specular_color.to_vec3())
+        * (intersect.material.albedo[1] * specular_intensity * light_intensity);
+    //luz (en flotante para no recortar emisiones fuertes)
+    let emission = intersect.material.emission_at(intersect.u, intersect.v);
+
+    let mut surface = diffuse + specular;
+
+    // Iluminación indirecta: un rebote difuso desde el primer impacto (sangrado de color)
+    if settings.indirect_samples > 0 && depth == 0 && settings.max_depth > 0 {
+        let bounce_origin = intersect.point + intersect.normal * 1e-3;
+        let mut indirect = Vec3::zeros();
+        let rotation = rng.next_f32();
+        for i in 0..settings.indirect_samples {
+            let (u1, u2) = spiral_2d(i, settings.indirect_samples, rotation);
+            let bounce_direction = cosine_weighted_hemisphere(&intersect.normal, u1, u2);
+            let (bounced, _) = cast_ray(&bounce_origin, &bounce_direction, scene, light, settings, depth + 1, None);
+            indirect += bounced;
+        }
+        indirect /= settings.indirect_samples as f32;
+
+        let base_color = base_color.map(|c| c.min(1.0));
+        surface += indirect.component_mul(&base_color) * intersect.material.albedo[0];
     }
 
-    let specular_intensity = view_dir.dot(&reflect_dir).max(0.0).powf(intersect.material.specular);
-    let specular = light.color * intersect.material.albedo[1] * specular_intensity * light.intensity;
-    //luz
-    let emission = intersect.material.emission * 1.8;
+    // Reflexión ponderada por Fresnel (Schlick) en materiales con índice de refracción
+    let reflectivity = intersect.material.reflectivity(view_dir.dot(&intersect.normal));
+    if reflectivity > 0.0 && depth < settings.max_depth {
+        let reflect_origin = intersect.point + intersect.normal * 1e-3;
+        let reflect_direction = reflect(ray_direction, &intersect.normal).normalize();
+        let (reflected, _) = cast_ray(&reflect_origin, &reflect_direction, scene, light, settings, depth + 1, None);
+
+        let reflected = reflected.component_mul(&intersect.material.reflection_tint());
+        surface = surface * (1.0 - reflectivity) + reflected * reflectivity;
+    }
 
-    diffuse + specular + emission
+    (surface + emission, intersect.distance)
 }
 
-pub fn render(framebuffer: &mut Framebuffer, cubes: &[Cube], rectangles: &[RectangularPrism],camera: &Camera, light: &Light) {
+// Devuelve cuántos rayos primarios se lanzaron (con muestreo adaptativo varía por pixel)
+pub fn render(
+    framebuffer: &mut Framebuffer,
+    scene: &Scene,
+    camera: &Camera,
+    light: &Light,
+    settings: &RenderSettings,
+    mut on_tile_done: Option<&mut dyn FnMut(usize, usize)>,
+) -> u64 {
     let width = framebuffer.width as f32;
     let height = framebuffer.height as f32;
     let aspect_ratio = width / height;
-    let fov = PI / 3.0;
-    let perspective_scale = (fov * 0.5).tan();
+    let perspective_scale = (camera.fov() * 0.5).tan();
+    let (ortho_half_width, ortho_half_height) = camera.ortho_half_extents(aspect_ratio);
 
-    for y in 0..framebuffer.height {
-        for x in 0..framebuffer.width {
-            let screen_x = (2.0 * x as f32) / width - 1.0;
-            let screen_y = -(2.0 * y as f32) / height + 1.0;
+    let camera_forward = camera.forward();
+    let camera_right = camera.right();
+    let camera_up = camera.up();
 
-            let screen_x = screen_x * aspect_ratio * perspective_scale;
-            let screen_y = screen_y * perspective_scale;
+    // Rayo primario que pasa por la posición (x, y) en pixeles
+    let primary_ray = |x: f32, y: f32| -> (Vec3, Vec3) {
+        let screen_x = (2.0 * x) / width - 1.0;
+        let screen_y = -(2.0 * y) / height + 1.0;
 
-            let ray_direction = normalize(&Vec3::new(screen_x, screen_y, -1.0));
-            let rotated_direction = camera.base_change(&ray_direction);
+        match camera.projection {
+            Projection::Perspective => {
+                let screen_x = screen_x * aspect_ratio * perspective_scale;
+                let screen_y = screen_y * perspective_scale;
 
-            let pixel_color = cast_ray(&camera.eye, &rotated_direction, cubes, rectangles,light);
+                let ray_direction = normalize(&Vec3::new(screen_x, screen_y, -1.0));
+                (camera.eye, camera.base_change(&ray_direction))
+            }
+            // Rayos paralelos: el origen se desplaza sobre el plano de imagen
+            Projection::Orthographic => {
+                let origin = camera.eye
+                    + camera_right * (screen_x * ortho_half_width)
+                    + camera_up * (screen_y * ortho_half_height);
+                (origin, camera_forward)
+            }
+        }
+    };
+    let samples = settings.max_samples();
+
+    // Objetos fuera del campo de visión no se prueban con los rayos primarios
+    let visible = scene.visible_objects(&Frustum::from_camera(camera, aspect_ratio));
+
+    // Solo la región de recorte; los pixeles de afuera no se tocan
+    let (region_x, region_y, region_end_x, region_end_y) = match settings.crop {
+        Some(crop) => crop.clamped(framebuffer.width, framebuffer.height),
+        None => (0, 0, framebuffer.width, framebuffer.height),
+    };
+
+    // Recorrer la región por bloques (tiles) de tile_size x tile_size
+    let tile_size = settings.tile_size.max(1);
+    let tiles_x = (region_end_x - region_x).div_ceil(tile_size);
+    let tiles_y = (region_end_y - region_y).div_ceil(tile_size);
+    let total_tiles = tiles_x * tiles_y;
+    let mut tiles_done = 0;
+    let mut primary_rays = 0;
+
+    for tile_y in 0..tiles_y {
+        for tile_x in 0..tiles_x {
+            let x_start = region_x + tile_x * tile_size;
+            let y_start = region_y + tile_y * tile_size;
+            let x_end = (x_start + tile_size).min(region_end_x);
+            let y_end = (y_start + tile_size).min(region_end_y);
+
+            for y in y_start..y_end {
+                for x in x_start..x_end {
+                    // Una muestra en la esquina del pixel, o varias repartidas con Halton
+                    let mut pixel_color = Vec3::zeros();
+                    let mut depth = f32::INFINITY;
+                    let (mut luminance_sum, mut luminance_squares) = (0.0, 0.0);
+                    let mut taken = 0;
+                    for sample in 0..samples {
+                        let (offset_x, offset_y) = if samples == 1 {
+                            (0.0, 0.0)
+                        } else {
+                            halton_2d((settings.seed as u32).wrapping_add(sample + 1))
+                        };
+                        let (ray_origin, ray_direction) = primary_ray(x as f32 + offset_x, y as f32 + offset_y);
+                        let (color, hit_distance) = if settings.debug_view == DebugView::Normals {
+                            normal_color(&ray_origin, &ray_direction, scene, &visible)
+                        } else {
+                            cast_ray(&ray_origin, &ray_direction, scene, light, settings, 0, Some(&visible))
+                        };
+                        pixel_color += color;
+                        depth = depth.min(hit_distance);
+                        taken += 1;
 
-            framebuffer.set_current_color(pixel_color.to_hex());
-            framebuffer.point(x, y);
+                        // Zonas planas se detienen en el mínimo de muestras
+                        let luminance = color.dot(&Vec3::new(0.2126, 0.7152, 0.0722));
+                        luminance_sum += luminance;
+                        luminance_squares += luminance * luminance;
+                        if settings.adaptive_sampling.is_some_and(|adaptive| adaptive.converged(taken, luminance_sum, luminance_squares)) {
+                            break;
+                        }
+                    }
+                    pixel_color /= taken as f32;
+                    primary_rays += taken as u64;
+                    let mapped = if settings.debug_view == DebugView::Normals {
+                        pixel_color //los colores ya son la normal, sin exposición ni tone mapping
+                    } else {
+                        pixel_color *= settings.exposure;
+                        apply_gamma(&settings.tone_mapping.apply(&pixel_color), settings.gamma)
+                    };
+
+                    let alpha = if depth.is_finite() { 255 } else { 0 }; //fondo transparente
+                    framebuffer.set_current_color(Color::from_vec3(&mapped).to_hex_with_alpha(alpha));
+                    framebuffer.set_current_depth(depth);
+                    framebuffer.set_current_hdr(pixel_color);
+                    let object_id = if settings.record_object_ids {
+                        let (ray_origin, ray_direction) = primary_ray(x as f32, y as f32);
+                        scene.nearest_hit_among(&visible, &ray_origin, &ray_direction).object_id
+                    } else {
+                        None
+                    };
+                    framebuffer.set_current_object_id(object_id);
+                    framebuffer.point(x, y);
+                }
+            }
+
+            tiles_done += 1;
+            if let Some(callback) = on_tile_done.as_mut() {
+                callback(tiles_done, total_tiles);
+            }
         }
     }
+
+    // Post-proceso sobre el buffer HDR completo (no con recorte: cambiaría pixeles de afuera)
+    if settings.crop.is_some() {
+        return primary_rays;
+    }
+    if let Some(bloom) = settings.bloom.as_ref().filter(|_| settings.debug_view != DebugView::Normals) {
+        bloom.apply(&mut framebuffer.hdr, framebuffer.width, framebuffer.height);
+        framebuffer.tone_map(settings.tone_mapping, settings.gamma);
+    }
+
+    if settings.debug_view == DebugView::Edges {
+        let edges = depth_edges(&framebuffer.depth, framebuffer.width, framebuffer.height);
+        framebuffer.show_grayscale(&edges);
+    }
+
+    if settings.show_bounds {
+        draw_bounds(framebuffer, scene, camera);
+    }
+
+    primary_rays
 }
 
-fn main() {
-    let window_width = 800;
-    let window_height = 600;
-    let framebuffer_width = 800;
-    let framebuffer_height = 600;
-    let frame_delay = Duration::from_millis(16);
+// Vista de normales: cada componente de [-1, 1] pasa a [0, 1]
+fn normal_color(ray_origin: &Vec3, ray_direction: &Vec3, scene: &Scene, candidates: &[usize]) -> (Vec3, f32) {
+    let intersect = scene.nearest_hit_among(candidates, ray_origin, ray_direction);
+    if !intersect.is_intersecting {
+        return (Vec3::zeros(), f32::INFINITY);
+    }
+    (intersect.normal * 0.5 + Vec3::new(0.5, 0.5, 0.5), intersect.distance)
+}
 
-    let mut framebuffer = Framebuffer::new(framebuffer_width, framebuffer_height);
+// Aristas de las cajas de cada objeto proyectadas sobre la imagen
+fn draw_bounds(framebuffer: &mut Framebuffer, scene: &Scene, camera: &Camera) {
+    const EDGES: [(usize, usize); 12] = [
+        (0, 1), (1, 2), (2, 3), (3, 0),
+        (4, 5), (5, 6), (6, 7), (7, 4),
+        (0, 4), (1, 5), (2, 6), (3, 7),
+    ];
+    let width = framebuffer.width as f32;
+    let height = framebuffer.height as f32;
+    let limit = 4.0 * width.max(height); // Evitar lineas enormes cerca de la camara
 
-    let mut window = Window::new(
-        "Diorama",
-        window_width,
-        window_height,
-        WindowOptions::default(),
-    ).unwrap();
+    framebuffer.set_current_color(0xFF00FF00);
+    for object in scene.objects.iter().filter(|obj| obj.is_enabled()) {
+        let Some(bounds) = object.bounding_box() else {
+            continue;
+        };
+        let corners = bounds.corners().map(|corner| {
+            camera.project(&corner, width / height).map(|(screen_x, screen_y)| {
+                ((screen_x + 1.0) * 0.5 * width, (1.0 - screen_y) * 0.5 * height)
+            })
+        });
+
+        for (a, b) in EDGES {
+            if let (Some((x0, y0)), Some((x1, y1))) = (corners[a], corners[b]) {
+                if [x0, y0, x1, y1].iter().any(|c| c.abs() > limit) {
+                    continue;
+                }
+                framebuffer.draw_line(x0 as i32, y0 as i32, x1 as i32, y1 as i32);
+            }
+        }
+    }
+}
 
-    let wood_texture = Material::load_texture("textures/wood.png");
+// Si falla se avisa y se usa el tablero de Texture::missing para que se note en el render
+fn load_texture_or_placeholder(texture_cache: &mut TextureCache, path: &str) -> Arc<Texture> {
+    texture_cache.load(path).unwrap_or_else(|e| {
+        eprintln!("Error al cargar la textura {}: {}", path, e);
+        Arc::new(Texture::missing())
+    })
+}
+
+// Igual para un material con texture_path
+fn build_or_placeholder(builder: MaterialBuilder, path: &str) -> Material {
+    builder.clone().texture_path(path).build().unwrap_or_else(|e| {
+        eprintln!("Error al cargar la textura {}: {}", path, e);
+        builder.texture(Some(Arc::new(Texture::missing()))).build().expect("sin texture_path no se carga nada")
+    })
+}
+
+// max_texture_size: reducir texturas grandes al cargarlas (ahorra memoria)
+// library: materiales de --materials que reemplazan a los del mismo nombre
+fn build_scene(max_texture_size: Option<u32>, library: Option<&MaterialLibrary>) -> Scene {
+    let mut texture_cache = TextureCache::new();
+    texture_cache.max_texture_size = max_texture_size;
+    let wood_texture = Some(load_texture_or_placeholder(&mut texture_cache, "textures/wood.png"));
     let wood = Material::new(
         Color::new(101, 62, 4),
         20.0,
@@ -132,15 +426,24 @@ fn main() {
         wood_texture,
         Color::new(0, 0, 0)
     );
-    let grass_texture = Material::load_texture("textures/grass.png");
-    let grass = Material::new(
+    let grass_texture = Some(load_texture_or_placeholder(&mut texture_cache, "textures/grass.png"));
+    let mut grass = Material::new(
         Color::new(29,	60,	14), 
         7.0, 
         [0.7, 0.1],
         grass_texture,
         Color::new(0, 0, 0)
     );
-    let leaves_texture = Material::load_texture("textures/leaves.png");
+    grass.uv_scale = [4.0, 4.0]; // Repetir sobre la base
+    grass.wrap_mode = WrapMode::Repeat;
+    let lily_pad = Material::new(
+        Color::new(52, 110, 38),
+        12.0,
+        [0.8, 0.1],
+        None,
+        Color::new(0, 0, 0)
+    );
+    let leaves_texture = Some(load_texture_or_placeholder(&mut texture_cache, "textures/leaves.png"));
     let leaves = Material::new(
         Color::new(29,	60,	14), 
         7.0, 
@@ -148,7 +451,7 @@ fn main() {
         leaves_texture,
         Color::new(0, 0, 0)
     );
-    let wall_texture = Material::load_texture("textures/wall.png");
+    let wall_texture = Some(load_texture_or_placeholder(&mut texture_cache, "textures/wall.png"));
     let wall = Material::new(
         Color::new(206, 100, 0),
         15.0,
@@ -156,7 +459,7 @@ fn main() {
         wall_texture,
         Color::new(0, 0, 0)
     );
-    let roof_texture = Material::load_texture("textures/roof.png");
+    let roof_texture = Some(load_texture_or_placeholder(&mut texture_cache, "textures/roof.png"));
     let roof = Material::new(
         Color::new(38,55,71),
         14.0,
@@ -164,242 +467,267 @@ fn main() {
         roof_texture,
         Color::new(0, 0, 0)
     );
-    let water_texture = Material::load_texture("textures/water.png");
-    let water = Material::new(
+    let water_texture = Some(load_texture_or_placeholder(&mut texture_cache, "textures/water.png"));
+    let mut water = Material::new(
         Color::new(61, 133, 198),
         5.0,
         [0.7, 0.04],
         water_texture,
         Color::new(0, 0, 0)
     );
-
-
-    let windows = Material::new(
-        Color::new(253, 237, 191), 
-        0.0, 
-        [1.0, 0.0], // Solo emisión
-        None,
-        Color::new(253, 237, 191)* 2.0
+    water.refractive_index = Some(1.33);
+    water.wrap_mode = WrapMode::Repeat;
+    water.uv_scroll = [0.03, 0.01]; //corriente lenta
+    water.ripples = Some(Ripples::new(0.08, 6.0, 1.5));
+    let named = |name: &str, material: Material| library.and_then(|library| library.get(name)).unwrap_or(material);
+    let (wood, grass, lily_pad, leaves, wall, roof, water) = (
+        named("wood", wood),
+        named("grass", grass),
+        named("lily_pad", lily_pad),
+        named("leaves", leaves),
+        named("wall", wall),
+        named("roof", roof),
+        named("water", water),
     );
+    //postes del muelle: la veta vertical
+    let mut dock_post = wood.clone();
+    dock_post.texture_rotation = PI / 2.0;
+
+    let windows = Material::builder()
+        .diffuse(Color::new(253, 237, 191))
+        .albedo([1.0, 0.0]) // Solo emisión
+        .emission_hdr(Color::new(253, 237, 191).to_vec3() * 2.0)
+        .emission_texture(Some(Arc::new(window_glow())))
+        .emission_strength(1.8)
+        .build()
+        .expect("sin texture_path no se carga nada");
     //luna/sol
-    let light_cube_texture = Material::load_texture("textures/moon.png");
+    let moon = Material::builder()
+        .diffuse(Color::new(228, 246, 255) * 1.5)
+        .specular(11.0)
+        .albedo([0.5, 0.5])
+        .max_texture_size(max_texture_size)
+        .emission_hdr(Color::new(228, 246, 255).to_vec3() * 1.5) //en flotante, sin recortar a 255
+        .emission_strength(1.8);
+    let moon = build_or_placeholder(moon, "textures/moon.png");
+    //Debe ser el primer objeto de la escena (MOON_INDEX)
     let light_cube = Cube {
         center: Vec3::new(0.0, 5.0, -5.0),
         side_length: 1.0,
-        material: Material::new(
-            Color::new(228, 246, 255)*1.5, 
-            11.0, 
-            [0.5, 0.5], 
-            light_cube_texture,
-            Color::new(228, 246, 255)* 1.5
-        )
+        material: moon,
+        enabled: true
     };
 
-    let cubes = [
+    let cubes = vec![
         light_cube,
         //Arbol 1 hojas
         Cube {
             center: Vec3::new(1.7, 1.2, -3.2),
             side_length: 0.74,
-            material: leaves.clone()
+            material: leaves.clone(),
+            enabled: true
         },
         Cube {
             center: Vec3::new(1.0, 1.3, -2.8),
             side_length: 0.74,
-            material: leaves.clone()
+            material: leaves.clone(),
+            enabled: true
         },
         Cube {
             center: Vec3::new(0.8, 0.9, -3.4),
             side_length: 0.74,
-            material: leaves.clone()
+            material: leaves.clone(),
+            enabled: true
         },
         Cube {
             center: Vec3::new(0.5, 1.2, -3.5),
             side_length: 0.74,
-            material: leaves.clone()
+            material: leaves.clone(),
+            enabled: true
         },
         Cube {
             center: Vec3::new(1.2, 1.6, -3.3),
             side_length: 0.74,
-            material: leaves.clone()
+            material: leaves.clone(),
+            enabled: true
         },
         Cube {
             center: Vec3::new(1.0, 1.1, -3.8),
             side_length: 0.74,
-            material: leaves.clone()
+            material: leaves.clone(),
+            enabled: true
         },
         //Arbol 2 hojas
         Cube {
             center: Vec3::new(-3.3, 0.8, -4.3),
             side_length: 0.74,
-            material: leaves.clone()
+            material: leaves.clone(),
+            enabled: true
         },
         Cube {
             center: Vec3::new(-3.0, 1.5, -4.0),
             side_length: 0.74,
-            material: leaves.clone()
+            material: leaves.clone(),
+            enabled: true
         },
         Cube {
             center: Vec3::new(-2.9, 1.2, -4.2),
             side_length: 0.74,
-            material: leaves.clone()
+            material: leaves.clone(),
+            enabled: true
         },
         Cube {
             center: Vec3::new(-2.6, 1.1, -3.61),
             side_length: 0.74,
-            material: leaves.clone()
+            material: leaves.clone(),
+            enabled: true
         },
         Cube {
             center: Vec3::new(-3.4, 1.0, -3.7),
             side_length: 0.74,
-            material: leaves.clone()
+            material: leaves.clone(),
+            enabled: true
         },
         //Arbol 3 hojas
         Cube {
             center: Vec3::new(-1.2, 0.7, -1.8),
             side_length: 0.74,
-            material: leaves.clone()
+            material: leaves.clone(),
+            enabled: true
         },
         Cube {
             center: Vec3::new(-1.3, 1.3, -2.2),
             side_length: 0.74,
-            material: leaves.clone()
+            material: leaves.clone(),
+            enabled: true
         },
         Cube {
             center: Vec3::new(-1.92, 1.1, -2.2),
             side_length: 0.74,
-            material: leaves.clone()
+            material: leaves.clone(),
+            enabled: true
         },
         Cube {
             center: Vec3::new(-1.0, 1.0, -2.75),
             side_length: 0.74,
-            material: leaves.clone()
+            material: leaves.clone(),
+            enabled: true
         },
         Cube {
             center: Vec3::new(-1.7, 0.9, -2.4),
             side_length: 0.74,
-            material: leaves.clone()
+            material: leaves.clone(),
+            enabled: true
         },
         //arbol 4 hojas
         Cube {
             center: Vec3::new(-1.0, 0.7, -5.8),
             side_length: 0.74,
-            material: leaves.clone()
+            material: leaves.clone(),
+            enabled: true
         },
         Cube {
             center: Vec3::new(-1.0, 1.7, -6.0),
             side_length: 0.74,
-            material: leaves.clone()
+            material: leaves.clone(),
+            enabled: true
         },
         Cube {
             center: Vec3::new(-1.5, 1.4, -6.1),
             side_length: 0.74,
-            material: leaves.clone()
+            material: leaves.clone(),
+            enabled: true
         },
         Cube {
             center: Vec3::new(-0.5, 1.2, -6.2),
             side_length: 0.74,
-            material: leaves.clone()
+            material: leaves.clone(),
+            enabled: true
         },
         Cube {
             center: Vec3::new(-1.3, 1.1, -5.9),
             side_length: 0.74,
-            material: leaves.clone()
+            material: leaves.clone(),
+            enabled: true
         },
         Cube {
             center: Vec3::new(-0.3, 1.0, -5.95),
             side_length: 0.74,
-            material: leaves.clone()
+            material: leaves.clone(),
+            enabled: true
         },
         //techo orilla frente
         Cube {
             center: Vec3::new(3.5, 0.45, 2.3),
             side_length: 0.4,
-            material: wood.clone()
+            material: wood.clone(),
+            enabled: true
         },
         Cube {
             center: Vec3::new(3.9, 0.6, 2.3),
             side_length: 0.4,
-            material: wood.clone()
+            material: wood.clone(),
+            enabled: true
         },
         Cube {
             center: Vec3::new(4.3, 0.7, 2.3),
             side_length: 0.4,
-            material: wood.clone()
+            material: wood.clone(),
+            enabled: true
         },
         Cube {
             center: Vec3::new(4.7, 0.6, 2.3),
             side_length: 0.4,
-            material: wood.clone()
+            material: wood.clone(),
+            enabled: true
         },
         Cube {
             center: Vec3::new(5.1, 0.45, 2.3),
             side_length: 0.4,
-            material: wood.clone()
+            material: wood.clone(),
+            enabled: true
         },
         //techo orilla atras
         Cube {
             center: Vec3::new(3.5, 0.45, -1.7),
             side_length: 0.4,
-            material: wood.clone()
+            material: wood.clone(),
+            enabled: true
         },
         Cube {
             center: Vec3::new(3.9, 0.6, -1.7),
             side_length: 0.4,
-            material: wood.clone()
+            material: wood.clone(),
+            enabled: true
         },
         Cube {
             center: Vec3::new(4.3, 0.7, -1.7),
             side_length: 0.4,
-            material: wood.clone()
+            material: wood.clone(),
+            enabled: true
         },
         Cube {
             center: Vec3::new(4.7, 0.6, -1.7),
             side_length: 0.4,
-            material: wood.clone()
+            material: wood.clone(),
+            enabled: true
         },
         Cube {
             center: Vec3::new(5.1, 0.45, -1.7),
             side_length: 0.4,
-            material: wood.clone()
+            material: wood.clone(),
+            enabled: true
         }
     ];
-    let rectangles =[
+    let rectangles = vec![
         //base
         RectangularPrism{
             center: Vec3::new(1.0, -0.9, -2.0),
             width: 9.0,
             height: 0.3,
             depth: 9.0,
-            material: grass.clone()
-        },
-        //arboles (troncos)
-        RectangularPrism{
-            center: Vec3::new(-1.0, 0.3, -6.0),
-            width: 0.6,
-            height: 3.0,
-            depth: 0.6,
-            material: wood.clone()
-        },RectangularPrism{
-            center: Vec3::new(-3.0, 0.0, -4.0),
-            width: 0.6,
-            height: 2.0,
-            depth: 0.6,
-            material: wood.clone()
-        },
-        RectangularPrism{
-            center: Vec3::new(-1.5, 0.2,-2.4),
-            width: 0.6,
-            height: 2.5,
-            depth: 0.6,
-            material: wood.clone()
-        },
-        RectangularPrism{
-            center: Vec3::new(1.0, 0.0,-3.3),
-            width: 0.6,
-            height: 2.0,
-            depth: 0.6,
-            material: wood.clone()
+            material: grass.clone(),
+            enabled: true
         },
         //casa
         RectangularPrism{
@@ -407,7 +735,8 @@ fn main() {
             width: 1.8,
             height: 1.3,
             depth: 4.0,
-            material: wall.clone()
+            material: wall.clone(),
+            enabled: true
         },
         //ventanas
         RectangularPrism {
@@ -415,21 +744,24 @@ fn main() {
             width: 0.04,
             height: 0.45,
             depth: 0.5,
-            material: windows.clone()
+            material: windows.clone(),
+            enabled: true
         },
         RectangularPrism {
             center: Vec3::new(3.35, 0.13,1.5),
             width: 0.04,
             height: 0.45,
             depth: 0.5,
-            material: windows.clone()
+            material: windows.clone(),
+            enabled: true
         },
         RectangularPrism {
             center: Vec3::new(4.3, 0.15,2.4),
             width: 0.4,
             height: 0.4,
             depth: 0.04,
-            material: windows.clone()
+            material: windows.clone(),
+            enabled: true
         },
         //puerta
         RectangularPrism {
@@ -437,105 +769,469 @@ fn main() {
             width: 0.03,
             height: 0.9,
             depth: 0.5,
-            material: wood.clone()
+            material: wood.clone(),
+            enabled: true
         },        
-        //techo placa
+        //muelle
         RectangularPrism{
-            center: Vec3::new(4.3, 0.49,0.3),
-            width: 1.95,
+            center: Vec3::new(0.15,-0.7,0.2),
+            width: 1.4,
             height: 0.1,
-            depth: 4.0,
-            material: roof.clone()
+            depth: 0.8,
+            material: wood.clone(),
+            enabled: true
         },
-        RectangularPrism{
-            center: Vec3::new(4.3, 0.57,0.3),
-            width: 1.8,
-            height: 0.1,
-            depth: 4.0,
-            material: roof.clone()
+    ];
+    let cylinders = vec![
+        //arboles (troncos)
+        Cylinder {
+            base: Vec3::new(-1.0, -1.2, -6.0),
+            radius: 0.3,
+            height: 3.0,
+            material: wood.clone(),
+            enabled: true
         },
-        RectangularPrism{
-            center: Vec3::new(4.3, 0.66,0.28),
-            width: 1.65,
-            height: 0.1,
-            depth: 3.6,
-            material: roof.clone()
+        Cylinder {
+            base: Vec3::new(-3.0, -1.0, -4.0),
+            radius: 0.3,
+            height: 2.0,
+            material: wood.clone(),
+            enabled: true
         },
-        RectangularPrism{
-            center: Vec3::new(4.3, 0.75,0.28),
-            width: 1.5,
-            height: 0.1,
-            depth: 3.6,
-            material: roof.clone()
+        Cylinder {
+            base: Vec3::new(-1.5, -1.05, -2.4),
+            radius: 0.3,
+            height: 2.5,
+            material: wood.clone(),
+            enabled: true
         },
-        //lago
-        RectangularPrism{
-            center: Vec3::new(-0.9,-0.79,0.2),
-            width: 2.0,
-            height: 0.1,
-            depth: 3.0,
-            material: water.clone()
+        Cylinder {
+            base: Vec3::new(1.0, -1.0, -3.3),
+            radius: 0.3,
+            height: 2.0,
+            material: wood.clone(),
+            enabled: true
         },
-        RectangularPrism{
-            center: Vec3::new(-0.9,-0.79,0.2),
-            width: 2.5,
-            height: 0.1,
-            depth: 2.6,
-            material: water.clone()
+        //muelle (postes)
+        Cylinder {
+            base: Vec3::new(-0.4, -0.75, -0.2),
+            radius: 0.1,
+            height: 0.2,
+            material: dock_post.clone(),
+            enabled: true
         },
-        RectangularPrism{
-            center: Vec3::new(-0.9,-0.79,0.2),
-            width: 3.0,
-            height: 0.1,
-            depth: 2.3,
-            material: water.clone()
+        Cylinder {
+            base: Vec3::new(-0.4, -0.75, 0.6),
+            radius: 0.1,
+            height: 0.2,
+            material: dock_post.clone(),
+            enabled: true
         },
-        RectangularPrism{
-            center: Vec3::new(-0.9,-0.79,0.2),
-            width: 3.5,
-            height: 0.1,
-            depth: 1.9,
-            material: water.clone()
+    ];
+    //techo a dos aguas
+    let roofs = vec![
+        TriangularPrism {
+            base_center: Vec3::new(4.3, 0.44, 0.3),
+            width: 1.95,
+            depth: 4.0,
+            height: 0.4,
+            material: roof.clone(),
+            enabled: true
         },
-        //muelle
-        RectangularPrism{
-            center: Vec3::new(0.15,-0.7,0.2),
-            width: 1.4,
-            height: 0.1,
-            depth: 0.8,
-            material: wood.clone()
+    ];
+    let disks = vec![
+        //lago, apenas sobre la base
+        Disk {
+            center: Vec3::new(-0.9, -0.74, 0.2),
+            normal: Vec3::new(0.0, 1.0, 0.0),
+            radius: 1.5,
+            material: water.clone(),
+            enabled: true
         },
-        RectangularPrism{
-            center: Vec3::new(-0.4,-0.65,-0.2),
-            width: 0.2,
-            height: 0.2,
-            depth: 0.2,
-            material: wood.clone()
+        //hojas sobre el lago
+        Disk {
+            center: Vec3::new(-1.6, -0.735, -0.6),
+            normal: Vec3::new(0.0, 1.0, 0.0),
+            radius: 0.16,
+            material: lily_pad.clone(),
+            enabled: true
         },
-        RectangularPrism{
-            center: Vec3::new(-0.4,-0.65,0.6),
-            width: 0.2,
-            height: 0.2,
-            depth: 0.2,
-            material: wood.clone()
+        Disk {
+            center: Vec3::new(-1.2, -0.735, 1.1),
+            normal: Vec3::new(0.0, 1.0, 0.0),
+            radius: 0.12,
+            material: lily_pad.clone(),
+            enabled: true
+        },
+        Disk {
+            center: Vec3::new(-0.5, -0.735, 1.2),
+            normal: Vec3::new(0.0, 1.0, 0.0),
+            radius: 0.1,
+            material: lily_pad.clone(),
+            enabled: true
         },
     ];
+    let mut scene = Scene::new();
+    for cube in cubes {
+        scene.add_object(Box::new(cube));
+    }
+    for rectangle in rectangles {
+        scene.add_object(Box::new(rectangle));
+    }
+    for cylinder in cylinders {
+        scene.add_object(Box::new(cylinder));
+    }
+    for roof in roofs {
+        scene.add_object(Box::new(roof));
+    }
+    for disk in disks {
+        scene.add_object(Box::new(disk));
+    }
+    scene.background = sky(false);
+
+    scene
+}
+
+// Escena, camara y luz de cada valor de --scene
+fn builtin_scene(scene: BuiltinScene, max_texture_size: Option<u32>, library: Option<&MaterialLibrary>) -> (Scene, Camera, Light) {
+    match scene {
+        BuiltinScene::Default => (build_scene(max_texture_size, library), default_camera(), default_light()),
+        BuiltinScene::Empty => (empty_scene(), default_camera(), default_light()),
+        BuiltinScene::Cornell => (cornell_box(), cornell_camera(), cornell_light()),
+    }
+}
+
+// Sin objetos, solo el cielo
+fn empty_scene() -> Scene {
+    let mut scene = Scene::new();
+    scene.background = sky(false);
+    scene
+}
+
+// Caja de Cornell: paredes blancas, izquierda roja, derecha verde, abierta hacia la camara
+fn cornell_box() -> Scene {
+    let matte = |color: Color| Material::new(color, 1.0, [0.9, 0.0], None, Color::new(0, 0, 0));
+    let white = matte(Color::new(200, 200, 200));
+    let red = matte(Color::new(180, 30, 25));
+    let green = matte(Color::new(35, 150, 40));
+    let panel = |center: Vec3, size: Vec3, material: &Material| RectangularPrism {
+        center,
+        width: size.x,
+        height: size.y,
+        depth: size.z,
+        material: material.clone(),
+        enabled: true,
+    };
+
+    let mut scene = Scene::new();
+    scene.background = Background::Solid(Color::new(0, 0, 0));
+    scene.ambient = Color::new(8, 8, 8);
+    let objects = [
+        panel(Vec3::new(0.0, -1.05, 0.0), Vec3::new(2.2, 0.1, 2.2), &white), //piso
+        panel(Vec3::new(0.0, 1.05, 0.0), Vec3::new(2.2, 0.1, 2.2), &white), //techo
+        panel(Vec3::new(0.0, 0.0, -1.05), Vec3::new(2.2, 2.2, 0.1), &white), //fondo
+        panel(Vec3::new(-1.05, 0.0, 0.0), Vec3::new(0.1, 2.2, 2.2), &red),
+        panel(Vec3::new(1.05, 0.0, 0.0), Vec3::new(0.1, 2.2, 2.2), &green),
+        panel(Vec3::new(-0.35, -0.4, -0.3), Vec3::new(0.6, 1.2, 0.6), &white), //caja alta
+        panel(Vec3::new(0.4, -0.7, 0.3), Vec3::new(0.6, 0.6, 0.6), &white), //caja baja
+    ];
+    for object in objects {
+        scene.add_object(Box::new(object));
+    }
+    scene
+}
+
+fn cornell_camera() -> Camera {
+    Camera::new(
+        Vec3::new(0.0, 0.0, 3.6),
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+    )
+}
+
+fn cornell_light() -> Light {
+    let mut light = Light::from_kelvin(Vec3::new(0.0, 0.85, 0.0), 6500.0, 1.2); //luz de día
+    light.radius = 0.15;
+    light
+}
+
+//ventana de cuatro vidrios: brillan los vidrios, el marco y la cruz no
+fn window_glow() -> Texture {
+    let glass = Color::new(255, 255, 255);
+    const SIZE: usize = 16;
+    let frame = |i: usize| i == 0 || i == SIZE - 1 || i == SIZE / 2 - 1 || i == SIZE / 2;
+    Texture::from_fn(SIZE, SIZE, |x, y| {
+        if frame(x) || frame(y) { Color::new(20, 12, 4) } else { glass }
+    })
+}
+
+//cielo: de día el color de fondo de siempre, de noche un degradado del horizonte al cenit
+fn sky(night: bool) -> Background {
+    if night {
+        Background::Gradient {
+            horizon: Color::new(14, 24, 52),
+            zenith: Color::new(2, 5, 16),
+        }
+    } else {
+        Background::Solid(Color::new(9, 20, 55))
+    }
+}
+
+// Indice del cubo de la luna/sol en la escena
+const MOON_INDEX: usize = 0;
+
+// Desplazamiento de la luz según el teclado numérico; `is_key_down` dice qué teclas están apretadas
+fn light_offset(is_key_down: impl Fn(Key) -> bool, speed: f32) -> Vec3 {
+    let axis = |negative: Key, positive: Key| {
+        let mut value = 0.0;
+        if is_key_down(negative) {
+            value -= speed;
+        }
+        if is_key_down(positive) {
+            value += speed;
+        }
+        value
+    };
+    Vec3::new(
+        axis(Key::NumPad4, Key::NumPad6),
+        axis(Key::NumPadMinus, Key::NumPadPlus),
+        axis(Key::NumPad8, Key::NumPad2),
+    )
+}
 
-    let mut camera = Camera::new(
+fn default_camera() -> Camera {
+    Camera::new(
         Vec3::new(-1.0, 1.0, 9.0),
         Vec3::new(0.0, 0.0, 0.0),
         Vec3::new(0.0, 1.0, 0.0),
-    );
-    //día y noche
+    )
+}
+
+// Vistas para presentar el diorama (C para recorrerlas)
+fn camera_presets() -> Vec<CameraPreset> {
+    vec![
+        CameraPreset {
+            name: "Frente",
+            eye: Vec3::new(-1.0, 1.0, 9.0),
+            center: Vec3::new(0.0, 0.0, 0.0),
+        },
+        CameraPreset {
+            name: "Desde arriba",
+            eye: Vec3::new(0.0, 9.0, 1.5),
+            center: Vec3::new(0.0, 0.0, 0.0),
+        },
+        CameraPreset {
+            name: "Muelle",
+            eye: Vec3::new(1.8, 0.2, 2.8),
+            center: Vec3::new(0.15, -0.7, 0.2),
+        },
+    ]
+}
+
+fn default_light() -> Light {
     let mut light = Light::new(
         Vec3::new(0.0, 5.1, 0.1),
         Color::new(255 ,236,183),
         1.7,
     );
+    light.radius = 0.4; // Sombras suaves con shadow_samples > 1
+    light
+}
+
+// Medir el render de la escena por defecto, sin ventana ni diorama.toml
+fn run_benchmark(width: usize, height: usize, iterations: usize) {
+    let mut renderer = match OffscreenRenderer::new(
+        width,
+        height,
+        build_scene(None, None),
+        default_camera(),
+        default_light(),
+        RenderSettings::new(),
+    ) {
+        Ok(renderer) => renderer,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let report = run_bench(&mut renderer, iterations);
+    println!("{} cuadros de {}x{} en {:.2?} ({:.2?} por cuadro)", report.iterations, width, height, report.total, report.frame_time());
+    println!("{:.0} rayos primarios/s", report.rays_per_second());
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = match CliOptions::parse(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if let Some(iterations) = options.bench_iterations {
+        run_benchmark(options.width, options.height, iterations);
+        return;
+    }
+
+    let window_width = options.width;
+    let window_height = options.height;
+    //minifb estira el framebuffer al tamaño de la ventana
+    let (framebuffer_width, framebuffer_height) = scaled_size(options.width, options.height, options.render_scale);
+    let frame_delay = Duration::from_millis(16);
+    //bloom (B para activar/desactivar, --bloom para empezar con él)
+    let bloom_threshold = 1.0;
+    let bloom_radius = 6;
+    let mut render_settings = RenderSettings::new();
+    if options.bloom {
+        render_settings.bloom = Some(Bloom::new(bloom_threshold, bloom_radius));
+    }
+    if let Some(samples) = options.samples {
+        render_settings.samples_per_pixel = samples;
+    }
+    if let Some(depth) = options.depth {
+        render_settings.max_depth = depth;
+    }
+    //sombras suaves (H para activar/desactivar, --shadow-samples N para empezar con ellas)
+    let soft_shadow_samples = options.shadow_samples.unwrap_or(8);
+    if let Some(samples) = options.shadow_samples {
+        render_settings.shadow_samples = samples;
+    }
+
+    let mut framebuffer = Framebuffer::new(framebuffer_width, framebuffer_height);
+    let material_library = options.materials.as_deref().map(|path| {
+        let mut texture_cache = TextureCache::new();
+        texture_cache.max_texture_size = options.max_texture_size;
+        MaterialLibrary::load(path, &mut texture_cache).unwrap_or_else(|e| {
+            eprintln!("{}: {}", path, e);
+            std::process::exit(1);
+        })
+    });
+    let (mut scene, mut camera, mut light) = builtin_scene(options.scene, options.max_texture_size, material_library.as_ref());
+    //Modelo .obj opcional encima de la escena elegida
+    if let Some(path) = options.model.as_deref() {
+        let material = Material::new(Color::new(200, 200, 200), 10.0, [0.9, 0.1], None, Color::new(0, 0, 0));
+        match Mesh::load_obj(path, material) {
+            Ok(triangles) => {
+                for triangle in triangles {
+                    scene.add_object(Box::new(triangle));
+                }
+            }
+            Err(e) => {
+                eprintln!("{}: {}", path, e);
+                std::process::exit(1);
+            }
+        }
+    }
+    //Cielo equirectangular en lugar del degradado
+    if let Some(path) = options.environment.as_deref() {
+        match Material::load_texture_with_max_size(path, options.max_texture_size) {
+            Ok(texture) => {
+                //La luz ambiental toma el tono del cielo
+                scene.ambient = texture.average_color() * 0.2;
+                scene.background = Background::Environment(Arc::new(texture));
+            }
+            Err(e) => {
+                eprintln!("Error al cargar el cielo {}: {}", path, e);
+                std::process::exit(1);
+            }
+        }
+    }
+    //Solo el diorama tiene el cubo de la luna que sigue a la luz
+    let mut moon_index = (options.scene == BuiltinScene::Default).then_some(MOON_INDEX);
+    //Sin ventana: vuelta completa de la camara
+    if let Some(output) = options.turntable_output.as_deref() {
+        if let Err(e) = render_turntable(&mut framebuffer, &scene, &mut camera, &light, &render_settings, options.turntable_frames, output) {
+            eprintln!("Error al exportar la vuelta en {}: {}", output, e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    //Sin ventana: renderizar un cuadro y guardarlo
+    if let Some(output) = options.headless_output.as_deref() {
+        render_settings.crop = options.crop;
+        render_headless(&mut framebuffer, &scene, &camera, &light, &render_settings, options.verbose);
+        //.ppm sin pasar por los codificadores de image, cualquier otra extensión como png
+        let saved = if output.ends_with(".ppm") {
+            framebuffer.save_ppm(output).map_err(|e| e.to_string())
+        } else {
+            framebuffer.save_png(output).map_err(|e| e.to_string())
+        };
+        if let Err(e) = saved {
+            eprintln!("Error al guardar {}: {}", output, e);
+            std::process::exit(1);
+        }
+        if let Some(depth_output) = options.depth_output.as_deref() {
+            if let Err(e) = framebuffer.save_depth_png(depth_output) {
+                eprintln!("Error al guardar {}: {}", depth_output, e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    //Restaurar el encuadre guardado con F5; solo en el diorama interactivo, los renders
+    //sin ventana y las otras escenas usan siempre su camara por defecto
+    if options.scene == BuiltinScene::Default {
+        if let Some(config) = ViewConfig::load(VIEW_CONFIG_PATH) {
+            camera = config.camera();
+            let previous_position = light.position;
+            config.apply_to_light(&mut light);
+            if let Some(index) = moon_index {
+                scene.translate_object(index, &(light.position - previous_position));
+            }
+        }
+    }
+
+    let mut window = Window::new(
+        "Diorama",
+        window_width,
+        window_height,
+        WindowOptions {
+            resize: true,
+            scale_mode: ScaleMode::Stretch,
+            ..WindowOptions::default()
+        },
+    ).unwrap();
+
     let new_light_intensity = 0.2;
+    let dock_position = Vec3::new(0.15, -0.7, 0.2);
+    let spot_cone_angle = PI / 8.0;
     let mut light_on = false;
+    //K: sol direccional; se guarda la luz anterior para volver a ella
+    let sun_direction = Vec3::new(-1.0, -2.0, -0.5);
+    let mut light_before_sun: Option<Light> = None;
+    //oclusión ambiental (O para activar)
+    let ao_samples = 8;
+    let ao_radius = 0.5;
+    //iluminación indirecta (I para activar, es lenta)
+    let indirect_samples = 8;
 
     let rotation_speed = PI / 10.0;
+    let move_speed = 0.2;
+    let roll_speed = PI / 60.0;
+    let light_speed = 0.1;
+    let mouse_sensitivity = 0.01;
+    let pan_sensitivity = 0.0015;
+    let exposure_step = 1.1;
+    let gamma_step = 0.05;
+    let mut last_mouse_pos: Option<(f32, f32)> = None;
+    //Click derecho: mostrar qué objeto está bajo el mouse
+    let mut right_was_down = false;
+    let mut pick_at: Option<(f32, f32)> = None;
+    //Supr: quitar de la escena el último objeto elegido
+    let mut picked: Option<usize> = None;
+    let start_time = Instant::now();
+    let presets = camera_presets();
+    let mut preset_index: Option<usize> = None;
+    //M: acumular cuadros mientras nada cambia (la animación se pausa)
+    let mut accumulate = false;
+    let mut accumulator = Accumulator::new();
+    let mut paused_time = 0.0;
+    //Cada cuadro acumulado usa base_seed + cuadros para tener otro ruido
+    let base_seed = render_settings.seed;
 
     while window.is_open() && !window.is_key_down(Key::Escape) {
         if window.is_key_down(Key::Left) {
@@ -550,30 +1246,954 @@ fn main() {
         if window.is_key_down(Key::Down) {
             camera.orbit(0.0, rotation_speed);
         }
-        if window.is_key_down(Key::W) {
-            camera.adjust_zoom(0.9);
+        //Arrastrar con click izquierdo para orbitar, con Shift para desplazar el pivote
+        let shift_down = window.is_key_down(Key::LeftShift) || window.is_key_down(Key::RightShift);
+        let mouse_pos = window.get_mouse_pos(MouseMode::Pass);
+        if window.get_mouse_down(MouseButton::Left) {
+            if let (Some((x, y)), Some((last_x, last_y))) = (mouse_pos, last_mouse_pos) {
+                if shift_down {
+                    let distance = (camera.eye - camera.center).magnitude();
+                    let (dx, dy) = drag_to_pan(x - last_x, y - last_y, pan_sensitivity, distance);
+                    camera.pan(dx, dy);
+                } else {
+                    let (yaw, pitch) = drag_to_orbit(x - last_x, y - last_y, mouse_sensitivity);
+                    camera.orbit(yaw, pitch);
+                }
+            }
+            last_mouse_pos = mouse_pos;
+        } else {
+            last_mouse_pos = None;
+        }
+        let right_down = window.get_mouse_down(MouseButton::Right);
+        if right_down && !right_was_down {
+            pick_at = mouse_pos;
         }
-        if window.is_key_down(Key::S) {
-            camera.adjust_zoom(1.1);
+        right_was_down = right_down;
+        //Rueda del mouse para zoom
+        let scroll = window.get_scroll_wheel();
+        if let Some((_, scroll_y)) = scroll {
+            if scroll_y > 0.0 {
+                camera.adjust_zoom(0.9);
+            } else if scroll_y < 0.0 {
+                camera.adjust_zoom(1.1);
+            }
         }
 
-        if window.is_key_pressed(Key::L, KeyRepeat::No) {
+        //Shift + WASDQE: mover camara libremente
+        if shift_down {
+            if window.is_key_down(Key::W) {
+                camera.translate(Vec3::new(0.0, 0.0, -move_speed));
+            }
+            if window.is_key_down(Key::S) {
+                camera.translate(Vec3::new(0.0, 0.0, move_speed));
+            }
+            if window.is_key_down(Key::A) {
+                camera.translate(Vec3::new(-move_speed, 0.0, 0.0));
+            }
+            if window.is_key_down(Key::D) {
+                camera.translate(Vec3::new(move_speed, 0.0, 0.0));
+            }
+            if window.is_key_down(Key::Q) {
+                camera.translate(Vec3::new(0.0, -move_speed, 0.0));
+            }
+            if window.is_key_down(Key::E) {
+                camera.translate(Vec3::new(0.0, move_speed, 0.0));
+            }
+        } else {
+            if window.is_key_down(Key::W) {
+                camera.adjust_zoom(0.9);
+            }
+            if window.is_key_down(Key::S) {
+                camera.adjust_zoom(1.1);
+            }
+            if window.is_key_down(Key::Q) {
+                camera.roll(-roll_speed);
+            }
+            if window.is_key_down(Key::E) {
+                camera.roll(roll_speed);
+            }
+        }
+
+        //Teclado numérico: mover la luz (y el cubo de la luna con ella)
+        let offset = light_offset(|key| window.is_key_down(key), light_speed);
+        if offset != Vec3::zeros() {
+            light.translate(offset);
+            if let Some(index) = moon_index {
+                scene.translate_object(index, &offset);
+            }
+            if light_on {
+                light.direction = (dock_position - light.position).normalize();
+            }
+        }
+
+        if window.is_key_pressed(Key::Delete, KeyRepeat::No) {
+            if let Some(index) = picked.take() {
+                scene.remove_object(index);
+                //Los índices después del quitado bajan uno
+                moon_index = match moon_index {
+                    Some(moon) if moon == index => None,
+                    Some(moon) if moon > index => Some(moon - 1),
+                    moon => moon,
+                };
+            }
+        }
+
+        if window.is_key_pressed(Key::K, KeyRepeat::No) {
+            match light_before_sun.take() {
+                Some(previous) => light = previous,
+                None => {
+                    let sun = Light::directional(sun_direction, light.color, 1.2);
+                    light_before_sun = Some(std::mem::replace(&mut light, sun));
+                }
+            }
+        }
+
+        if window.is_key_pressed(Key::L, KeyRepeat::No) && light_before_sun.is_none() {
             if light_on {
                 //Día
-                light.intensity = 1.7;
+                light = Light { radius: light.radius, ..Light::new(light.position, light.color, 1.7) };
             } else {
-                //Noche
-                light.intensity = new_light_intensity;
+                //Noche: foco sobre el muelle
+                let direction = dock_position - light.position;
+                let spot = Light::spot(light.position, direction, spot_cone_angle, light.color, new_light_intensity);
+                light = Light { radius: light.radius, ..spot };
             }
             light_on = !light_on;
+            if options.environment.is_none() {
+                scene.background = sky(light_on);
+            }
+        }
+
+        //Solo se restaura el encuadre del diorama, así que solo ese se guarda
+        if window.is_key_pressed(Key::F5, KeyRepeat::No) && options.scene == BuiltinScene::Default {
+            match ViewConfig::capture(&camera, &light).save(VIEW_CONFIG_PATH) {
+                Ok(()) => println!("Vista guardada en {}", VIEW_CONFIG_PATH),
+                Err(e) => eprintln!("Error al guardar {}: {}", VIEW_CONFIG_PATH, e),
+            }
         }
 
-        render(&mut framebuffer, &cubes, &rectangles,&camera, &light);
+        if window.is_key_pressed(Key::C, KeyRepeat::No) {
+            let index = next_preset(preset_index, presets.len());
+            camera.apply_preset(&presets[index]);
+            println!("Vista: {}", presets[index].name);
+            preset_index = Some(index);
+        }
+
+        if window.is_key_pressed(Key::R, KeyRepeat::No) {
+            camera.reset();
+        }
+
+        if window.is_key_pressed(Key::F, KeyRepeat::No) {
+            if let Some(bounds) = scene.bounds() {
+                let aspect_ratio = framebuffer.width as f32 / framebuffer.height as f32;
+                camera.frame(&bounds, camera.fov(), aspect_ratio);
+            }
+        }
+
+        if window.is_key_pressed(Key::P, KeyRepeat::No) {
+            camera.toggle_projection();
+        }
+
+        if window.is_key_pressed(Key::O, KeyRepeat::No) {
+            render_settings.ambient_occlusion = match render_settings.ambient_occlusion {
+                Some(_) => None,
+                None => Some(AmbientOcclusion::new(ao_samples, ao_radius)),
+            };
+        }
+
+        if window.is_key_pressed(Key::B, KeyRepeat::No) {
+            render_settings.bloom = match render_settings.bloom {
+                Some(_) => None,
+                None => Some(Bloom::new(bloom_threshold, bloom_radius)),
+            };
+        }
+
+        if window.is_key_pressed(Key::H, KeyRepeat::No) {
+            render_settings.shadow_samples = if render_settings.shadow_samples > 1 { 1 } else { soft_shadow_samples.max(2) };
+        }
+
+        if window.is_key_pressed(Key::I, KeyRepeat::No) {
+            render_settings.indirect_samples = if render_settings.indirect_samples > 0 { 0 } else { indirect_samples };
+        }
+
+        if window.is_key_pressed(Key::V, KeyRepeat::No) {
+            render_settings.debug_view = render_settings.debug_view.next();
+        }
+
+        if window.is_key_pressed(Key::G, KeyRepeat::No) {
+            render_settings.show_bounds = !render_settings.show_bounds;
+        }
+
+        if window.is_key_pressed(Key::T, KeyRepeat::No) {
+            render_settings.tone_mapping = render_settings.tone_mapping.next();
+        }
+
+        //[ ] exposición, ; ' gamma
+        if window.is_key_pressed(Key::LeftBracket, KeyRepeat::Yes) || window.is_key_pressed(Key::RightBracket, KeyRepeat::Yes) {
+            let factor = if window.is_key_down(Key::RightBracket) { exposure_step } else { 1.0 / exposure_step };
+            render_settings.adjust_exposure(factor);
+            println!("Exposición: {:.2}", render_settings.exposure);
+        }
+        if window.is_key_pressed(Key::Semicolon, KeyRepeat::Yes) || window.is_key_pressed(Key::Apostrophe, KeyRepeat::Yes) {
+            let delta = if window.is_key_down(Key::Apostrophe) { gamma_step } else { -gamma_step };
+            render_settings.adjust_gamma(delta);
+            println!("Gamma: {:.2}", render_settings.gamma);
+        }
+
+        if window.is_key_pressed(Key::M, KeyRepeat::No) {
+            accumulate = !accumulate;
+            paused_time = start_time.elapsed().as_secs_f32();
+            if !accumulate {
+                window.set_title("Diorama");
+            }
+        }
+
+        //Cualquier tecla o movimiento del mouse puede cambiar la imagen: volver a empezar
+        let input = !window.get_keys().is_empty() || window.get_mouse_down(MouseButton::Left) || scroll.is_some();
+        //Las vistas de depuración y las cajas se dibujan encima del HDR, no se acumulan
+        let accumulating = accumulate && render_settings.debug_view == DebugView::Shaded && !render_settings.show_bounds;
+        if input || !accumulating {
+            accumulator.reset();
+        }
+
+        //Ventana minimizada: no hay nada que dibujar
+        let (window_width, window_height) = window.get_size();
+        if window_width == 0 || window_height == 0 {
+            window.update();
+            std::thread::sleep(frame_delay);
+            continue;
+        }
+        //Seguir el tamaño de la ventana para que el aspecto sea correcto
+        let (width, height) = scaled_size(window_width, window_height, options.render_scale);
+        if let Err(e) = framebuffer.resize(width, height) {
+            eprintln!("{}", e);
+        }
+
+        //Los ids solo se calculan en el cuadro que se consulta
+        render_settings.record_object_ids = pick_at.is_some();
+        if accumulating {
+            scene.update(paused_time);
+            render_settings.seed = base_seed.wrapping_add(accumulator.frames() as u64);
+            render(&mut framebuffer, &scene, &camera, &light, &render_settings, None);
+            accumulator.accumulate(&mut framebuffer, render_settings.max_samples(), render_settings.tone_mapping, render_settings.gamma);
+            window.set_title(&format!("Diorama - {} muestras", accumulator.sample_count()));
+        } else {
+            scene.update(start_time.elapsed().as_secs_f32());
+            render_settings.seed = base_seed;
+            render(&mut framebuffer, &scene, &camera, &light, &render_settings, None);
+        }
+        if let Some((x, y)) = pick_at.take() {
+            scene.store_object_ids(&framebuffer);
+            //El mouse está en coordenadas de la ventana, el framebuffer puede ser más chico
+            let pixel_x = (x * framebuffer.width as f32 / window_width as f32) as usize;
+            let pixel_y = (y * framebuffer.height as f32 / window_height as f32) as usize;
+            picked = scene.pick(pixel_x, pixel_y);
+            match picked {
+                Some(index) => println!("Objeto {}", index),
+                None => println!("Fondo"),
+            }
+        }
 
         window
-            .update_with_buffer(&framebuffer.buffer, framebuffer_width, framebuffer_height)
+            .update_with_buffer(&framebuffer.buffer, framebuffer.width, framebuffer.height)
             .unwrap();
 
         std::thread::sleep(frame_delay);
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::MaterialKind;
+    use crate::tone_mapping::ToneMapping;
+    use crate::render_settings::{AdaptiveSampling, Crop};
+    use crate::framebuffer::NO_OBJECT;
+
+    fn render_cornell(width: usize, height: usize, settings: &RenderSettings) -> Vec<u32> {
+        let mut framebuffer = Framebuffer::new(width, height);
+        render(&mut framebuffer, &cornell_box(), &cornell_camera(), &cornell_light(), settings, None);
+        framebuffer.buffer
+    }
+
+    #[test]
+    fn tiled_render_matches_scanline_render() {
+        let (width, height) = (40, 30);
+        let mut settings = RenderSettings::new();
+        settings.shadow_samples = 4;
+
+        settings.tile_size = 16;
+        let tiled = render_cornell(width, height, &settings);
+        settings.tile_size = width.max(height); //un solo tile: fila por fila
+        let scanline = render_cornell(width, height, &settings);
+
+        assert!(tiled == scanline);
+    }
+
+    #[test]
+    fn same_seed_same_image_different_seed_different_image() {
+        // Sombras suaves y oclusión ambiental para que el muestreo se note
+        let mut settings = RenderSettings::new();
+        settings.shadow_samples = 4;
+        settings.ambient_occlusion = Some(AmbientOcclusion::new(4, 0.5));
+
+        settings.seed = 1;
+        let first = render_cornell(40, 30, &settings);
+        let again = render_cornell(40, 30, &settings);
+        settings.seed = 2;
+        let other = render_cornell(40, 30, &settings);
+
+        assert!(first == again);
+        assert!(first != other);
+    }
+
+    #[test]
+    fn contributions_add_past_255_before_the_final_clamp() {
+        // Difuso, brillo y emisión de 0.9 + 0.9 + 200/255 por canal: cada uno cabe en u8, la suma no
+        let mut scene = Scene::new();
+        scene.ambient = Color::new(0, 0, 0);
+        let material = Material::new(Color::new(255, 255, 255), 1.0, [0.9, 0.9], None, Color::new(200, 200, 200));
+        scene.add_object(Box::new(RectangularPrism {
+            center: Vec3::zeros(),
+            width: 2.0,
+            height: 2.0,
+            depth: 2.0,
+            material,
+            enabled: true,
+        }));
+        let light = Light::new(Vec3::new(0.0, 10.0, 0.0), Color::new(255, 255, 255), 1.0);
+
+        let (color, _) = cast_ray(&Vec3::new(0.0, 5.0, 0.0), &Vec3::new(0.0, -1.0, 0.0), &scene, &light, &RenderSettings::new(), 0, None);
+        let expected = 0.9 + 0.9 + 200.0 / 255.0;
+        assert!((color - Vec3::new(expected, expected, expected)).magnitude() < 1e-4);
+        assert_eq!(Color::from_vec3(&color).to_hex(), 0xFFFFFF);
+    }
+
+    #[test]
+    fn tiny_area_light_matches_point_light() {
+        let mut scene = Scene::new();
+        scene.ambient = Color::new(0, 0, 0);
+        scene.add_object(Box::new(RectangularPrism {
+            center: Vec3::zeros(),
+            width: 4.0,
+            height: 0.2,
+            depth: 4.0,
+            material: Material::new(Color::new(200, 200, 200), 10.0, [0.9, 0.1], None, Color::new(0, 0, 0)),
+            enabled: true,
+        }));
+        let mut point = Light::new(Vec3::new(0.5, 2.0, 0.3), Color::new(255, 255, 255), 2.0);
+        point.attenuation = Some(0.5);
+        let mut area = Light::new(point.position, Color::new(255, 255, 255), 2.0);
+        area.attenuation = Some(0.5);
+        area.radius = 1e-3;
+        let mut settings = RenderSettings::new();
+        settings.shadow_samples = 8;
+
+        for x in [-1.5, 0.0, 0.8] {
+            let origin = Vec3::new(x, 5.0, 0.2);
+            let direction = Vec3::new(0.0, -1.0, 0.0);
+            let (from_point, _) = cast_ray(&origin, &direction, &scene, &point, &settings, 0, None);
+            let (from_area, _) = cast_ray(&origin, &direction, &scene, &area, &settings, 0, None);
+            assert!(from_point.magnitude() > 0.05);
+            assert!((from_point - from_area).magnitude() < 1e-3 * from_point.magnitude());
+        }
+    }
+
+    #[test]
+    fn pick_returns_the_cube_under_the_center_pixel() {
+        let mut scene = Scene::new();
+        let index = scene.add_object(Box::new(Cube {
+            center: Vec3::zeros(),
+            side_length: 1.0,
+            material: Material::black(),
+            enabled: true,
+        }));
+        let camera = Camera::new(Vec3::new(0.0, 0.0, 5.0), Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+        let light = Light::new(Vec3::new(0.0, 5.0, 0.0), Color::new(255, 255, 255), 1.0);
+        let mut settings = RenderSettings::new();
+        settings.record_object_ids = true;
+
+        let mut framebuffer = Framebuffer::new(40, 30);
+        render(&mut framebuffer, &scene, &camera, &light, &settings, None);
+        scene.store_object_ids(&framebuffer);
+
+        assert_eq!(scene.pick(20, 15), Some(index));
+        assert_eq!(scene.pick(0, 0), None); //fondo
+        assert_eq!(scene.pick(40, 15), None); //fuera de la imagen
+    }
+
+    #[test]
+    fn removing_an_added_object_restores_the_render() {
+        let mut scene = Scene::new();
+        scene.add_object(Box::new(Cube {
+            center: Vec3::zeros(),
+            side_length: 1.0,
+            material: Material::black(),
+            enabled: true,
+        }));
+        let camera = Camera::new(Vec3::new(0.0, 0.0, 5.0), Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+        let light = Light::new(Vec3::new(0.0, 5.0, 0.0), Color::new(255, 255, 255), 1.0);
+        let settings = RenderSettings::new();
+        let mut framebuffer = Framebuffer::new(40, 30);
+        render(&mut framebuffer, &scene, &camera, &light, &settings, None);
+        let before = framebuffer.buffer.clone();
+
+        let index = scene.add_object(Box::new(Cube {
+            center: Vec3::new(0.5, 0.5, 1.0),
+            side_length: 0.5,
+            material: Material::black(),
+            enabled: true,
+        }));
+        render(&mut framebuffer, &scene, &camera, &light, &settings, None);
+        assert_ne!(framebuffer.buffer, before);
+
+        assert!(scene.remove_object(index).is_some());
+        assert!(scene.remove_object(index).is_none());
+        render(&mut framebuffer, &scene, &camera, &light, &settings, None);
+        assert_eq!(framebuffer.buffer, before);
+    }
+
+    #[test]
+    fn each_builtin_scene_has_its_objects() {
+        //diorama: 33 cubos, 7 prismas, 6 cilindros, el techo y 4 discos
+        let expected = [("default", 51), ("empty", 0), ("cornell", 7)];
+        assert_eq!(expected.len(), BuiltinScene::NAMES.len());
+        for (name, count) in expected {
+            let (scene, _, _) = builtin_scene(BuiltinScene::from_name(name).unwrap(), Some(64), None);
+            assert_eq!(scene.objects.len(), count, "{}", name);
+        }
+    }
+
+    #[test]
+    fn near_cube_has_smaller_depth_than_far_cube() {
+        let mut scene = Scene::new();
+        for (x, z) in [(-1.0, 1.0), (1.0, -3.0)] {
+            scene.add_object(Box::new(Cube {
+                center: Vec3::new(x, 0.0, z),
+                side_length: 1.0,
+                material: Material::black(),
+                enabled: true,
+            }));
+        }
+        let camera = Camera::new(Vec3::new(0.0, 0.0, 6.0), Vec3::new(0.0, 0.0, -1.0), Vec3::new(0.0, 1.0, 0.0));
+        let light = Light::new(Vec3::new(0.0, 5.0, 0.0), Color::new(255, 255, 255), 1.0);
+        let mut framebuffer = Framebuffer::new(60, 40);
+        render(&mut framebuffer, &scene, &camera, &light, &RenderSettings::new(), None);
+
+        //Pixel del centro de cada cubo proyectado
+        let (width, height) = (framebuffer.width as f32, framebuffer.height as f32);
+        let depth_at = |point: Vec3| {
+            let (screen_x, screen_y) = camera.project(&point, width / height).unwrap();
+            let x = ((screen_x + 1.0) * 0.5 * width) as usize;
+            let y = ((1.0 - screen_y) * 0.5 * height) as usize;
+            framebuffer.depth[y * framebuffer.width + x]
+        };
+        let near = depth_at(Vec3::new(-1.0, 0.0, 1.5));
+        let far = depth_at(Vec3::new(1.0, 0.0, -2.5));
+        assert!(near.is_finite() && far.is_finite());
+        assert!(near < far);
+        assert_eq!(framebuffer.depth[0], f32::INFINITY); //fondo
+    }
+
+    #[test]
+    fn red_background_makes_every_miss_red() {
+        let mut scene = Scene::new();
+        scene.background = Background::Solid(Color::new(255, 0, 0));
+        scene.add_object(Box::new(Cube {
+            center: Vec3::zeros(),
+            side_length: 1.0,
+            material: Material::new(Color::new(200, 200, 200), 10.0, [0.9, 0.1], None, Color::new(0, 0, 0)),
+            enabled: true,
+        }));
+        let camera = Camera::new(Vec3::new(0.0, 0.0, 5.0), Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+        let light = Light::new(Vec3::new(0.0, 5.0, 0.0), Color::new(255, 255, 255), 1.0);
+        let mut settings = RenderSettings::new();
+        settings.tone_mapping = ToneMapping::Clamp;
+        let mut framebuffer = Framebuffer::new(40, 30);
+        render(&mut framebuffer, &scene, &camera, &light, &settings, None);
+
+        let misses: Vec<u32> = framebuffer.buffer.iter().zip(framebuffer.depth.iter())
+            .filter(|(_, depth)| depth.is_infinite())
+            .map(|(pixel, _)| pixel & 0xFFFFFF)
+            .collect();
+        assert!(misses.len() > 40 * 30 / 2);
+        assert!(misses.iter().all(|&pixel| pixel == 0xFF0000));
+        assert!(framebuffer.buffer[15 * 40 + 20] & 0xFFFFFF != 0xFF0000); //el cubo no
+    }
+
+    #[test]
+    fn default_scenes_use_the_solid_default_background() {
+        let direction = Vec3::new(0.0, 1.0, 0.0);
+        let expected = Color::new(9, 20, 55).to_vec3();
+        assert_eq!(Scene::new().background.sample(&direction), expected);
+        assert_eq!(empty_scene().background.sample(&direction), expected);
+    }
+
+    #[test]
+    fn missing_texture_becomes_the_placeholder() {
+        let texture = load_texture_or_placeholder(&mut TextureCache::new(), "textures/no_existe.png");
+        let placeholder = Texture::missing();
+        assert_eq!((texture.width, texture.height), (placeholder.width, placeholder.height));
+        assert_eq!(texture.data, placeholder.data);
+        assert_eq!(texture.sample(0.01, 0.01), Some([255, 0, 255, 255])); //magenta
+    }
+
+    #[test]
+    fn library_material_replaces_the_built_in_one() {
+        let library = MaterialLibrary::parse("[grass]\ndiffuse = [255, 0, 0]", &mut TextureCache::new()).unwrap();
+        let scene = build_scene(Some(64), Some(&library));
+        let default = build_scene(Some(64), None);
+        //la base del diorama es el primer prisma, justo después de los 33 cubos
+        let origin = Vec3::new(-3.0, 5.0, 2.0);
+        let direction = Vec3::new(0.0, -1.0, 0.0);
+        let hit = scene.nearest_hit(&origin, &direction);
+        assert_eq!(hit.object_id, Some(33));
+        assert_eq!(hit.material.diffuse.to_hex(), 0xFF0000);
+        assert_ne!(default.nearest_hit(&origin, &direction).material.diffuse.to_hex(), 0xFF0000);
+    }
+
+    #[test]
+    fn metal_reflections_are_tinted_and_dielectric_ones_are_not() {
+        let reflection = |kind: MaterialKind| {
+            let mut scene = Scene::new();
+            scene.ambient = Color::new(0, 0, 0);
+            scene.background = Background::Solid(Color::new(255, 255, 255));
+            //sin difuso ni brillo: solo queda el reflejo del fondo blanco
+            let material = Material::builder()
+                .diffuse(Color::new(200, 30, 30))
+                .albedo([0.0, 0.0])
+                .refractive_index(1.5)
+                .kind(kind)
+                .build()
+                .unwrap();
+            scene.add_object(Box::new(RectangularPrism {
+                center: Vec3::new(0.0, -0.5, 0.0),
+                width: 20.0,
+                height: 1.0,
+                depth: 20.0,
+                material,
+                enabled: true,
+            }));
+            let light = Light::new(Vec3::new(0.0, 5.0, 0.0), Color::new(255, 255, 255), 1.0);
+            let direction = Vec3::new(0.0, -0.3, -1.0).normalize();
+            cast_ray(&Vec3::new(0.0, 1.0, 2.0), &direction, &scene, &light, &RenderSettings::new(), 0, None).0
+        };
+
+        let dielectric = reflection(MaterialKind::Dielectric);
+        assert!(dielectric.x > 0.05);
+        assert!((dielectric.x - dielectric.y).abs() < 1e-5 && (dielectric.y - dielectric.z).abs() < 1e-5);
+        let metal = reflection(MaterialKind::Metal);
+        assert!(metal.x > 3.0 * metal.y);
+    }
+
+    // Cubo de lado 1 en el origen visto de frente desde z = 5
+    fn front_cube() -> (Scene, Camera, Light) {
+        let mut scene = Scene::new();
+        scene.add_object(Box::new(Cube {
+            center: Vec3::zeros(),
+            side_length: 1.0,
+            material: Material::new(Color::new(200, 200, 200), 10.0, [0.9, 0.1], None, Color::new(0, 0, 0)),
+            enabled: true,
+        }));
+        let camera = Camera::new(Vec3::new(0.0, 0.0, 5.0), Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+        let light = Light::new(Vec3::new(0.0, 5.0, 5.0), Color::new(255, 255, 255), 1.0);
+        (scene, camera, light)
+    }
+
+    #[test]
+    fn misses_are_transparent_and_hits_opaque() {
+        let (scene, camera, light) = front_cube();
+        let mut framebuffer = Framebuffer::new(40, 30);
+        render(&mut framebuffer, &scene, &camera, &light, &RenderSettings::new(), None);
+
+        assert_eq!(framebuffer.buffer[0] >> 24, 0);
+        assert_eq!(framebuffer.buffer[15 * 40 + 20] >> 24, 255);
+        for (pixel, depth) in framebuffer.buffer.iter().zip(framebuffer.depth.iter()) {
+            assert_eq!(pixel >> 24, if depth.is_finite() { 255 } else { 0 });
+        }
+        let rgba = framebuffer.to_rgba_bytes();
+        assert_eq!(rgba[3], 0);
+        assert_eq!(rgba[(15 * 40 + 20) * 4 + 3], 255);
+    }
+
+    #[test]
+    fn crop_leaves_the_outside_untouched() {
+        let (scene, camera, light) = front_cube();
+        let mut framebuffer = Framebuffer::new(40, 30);
+        framebuffer.buffer.fill(0x12345678);
+        let mut settings = RenderSettings::new();
+        settings.crop = Some(Crop::new(15, 10, 10, 10));
+        render(&mut framebuffer, &scene, &camera, &light, &settings, None);
+
+        for y in 0..30 {
+            for x in 0..40 {
+                let pixel = framebuffer.buffer[y * 40 + x];
+                let inside = (15..25).contains(&x) && (10..20).contains(&y);
+                assert_eq!(pixel == 0x12345678, !inside, "({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn normals_view_paints_a_plus_x_face_red() {
+        let (scene, _, light) = front_cube();
+        let camera = Camera::new(Vec3::new(5.0, 0.0, 0.0), Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+        let mut settings = RenderSettings::new();
+        settings.debug_view = DebugView::Normals;
+        let mut framebuffer = Framebuffer::new(40, 30);
+        render(&mut framebuffer, &scene, &camera, &light, &settings, None);
+
+        //0.5 * (1, 0, 0) + 0.5
+        assert_eq!(framebuffer.buffer[15 * 40 + 20] & 0xFFFFFF, 0xFF8080);
+    }
+
+    #[test]
+    fn orthographic_cube_stays_square_on_a_wide_framebuffer() {
+        let (mut scene, mut camera, light) = front_cube();
+        camera.projection = Projection::Orthographic;
+        let mut framebuffer = Framebuffer::new(800, 400);
+        render(&mut framebuffer, &scene, &camera, &light, &RenderSettings::new(), None);
+
+        let hits = |range: &mut dyn Iterator<Item = usize>| range.filter(|&i| framebuffer.depth[i].is_finite()).count();
+        let width = hits(&mut (200 * 800..201 * 800));
+        let height = hits(&mut (400..framebuffer.depth.len()).step_by(800));
+        assert!(width > 0);
+        assert!(width.abs_diff(height) <= 1, "{}x{}", width, height);
+
+        //El ancho extra se ve: un cubo a la derecha entra con aspecto 2 y no con aspecto 1
+        let right = scene.add_object(Box::new(Cube {
+            center: Vec3::new(5.0, 0.0, 0.0),
+            side_length: 1.0,
+            material: Material::black(),
+            enabled: true,
+        }));
+        assert!(scene.visible_objects(&Frustum::from_camera(&camera, 2.0)).contains(&right));
+        assert!(!scene.visible_objects(&Frustum::from_camera(&camera, 1.0)).contains(&right));
+    }
+
+    #[test]
+    fn strong_emission_survives_into_the_hdr_buffer() {
+        let (mut scene, camera, light) = front_cube();
+        let mut material = Material::black();
+        material.emission = Vec3::new(4.0, 4.0, 4.0);
+        scene.objects[0] = Box::new(Cube { center: Vec3::zeros(), side_length: 1.0, material, enabled: true });
+        scene.ambient = Color::new(0, 0, 0);
+        let mut framebuffer = Framebuffer::new(40, 30);
+        render(&mut framebuffer, &scene, &camera, &light, &RenderSettings::new(), None);
+
+        let center = framebuffer.hdr[15 * 40 + 20];
+        assert!((center.x - 4.0).abs() < 1e-3, "{:?}", center);
+    }
+
+    #[test]
+    fn too_small_a_shadow_bias_shadows_a_lit_floor() {
+        let mut scene = Scene::new();
+        scene.add_object(Box::new(RectangularPrism {
+            center: Vec3::new(0.0, -0.5, 0.0),
+            width: 20.0,
+            height: 1.0,
+            depth: 20.0,
+            material: Material::black(),
+            enabled: true,
+        }));
+        let light = Light::new(Vec3::new(3.0, 4.0, -2.0), Color::new(255, 255, 255), 1.0);
+        let eye = Vec3::new(0.3, 2.7, 6.1);
+        //Puntos del piso donde llegan rayos de la camara, todos con luz directa
+        let shadowed = |scene: &Scene| (0..400).filter(|i| {
+            let target = Vec3::new((i % 20) as f32 * 0.37 - 3.7, 0.0, (i / 20) as f32 * 0.29 - 2.9);
+            let direction = (target - eye).normalize();
+            let hit = scene.nearest_hit(&eye, &direction);
+            shadow_visibility(&hit.point, &hit.normal, scene, &light, 1, 0.0) < 1.0
+        }).count();
+
+        assert_eq!(shadowed(&scene), 0);
+        scene.shadow_bias = 0.0;
+        assert!(shadowed(&scene) > 0);
+    }
+
+    #[test]
+    fn flat_normal_map_shades_like_no_map() {
+        let (mut scene, _, light) = front_cube();
+        let camera = Camera::new(Vec3::new(3.0, 2.0, 4.0), Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+        let mut framebuffer = Framebuffer::new(40, 30);
+        render(&mut framebuffer, &scene, &camera, &light, &RenderSettings::new(), None);
+        let without_map = framebuffer.buffer.clone();
+
+        let mut material = Material::new(Color::new(200, 200, 200), 10.0, [0.9, 0.1], None, Color::new(0, 0, 0));
+        material.normal_map = Some(Arc::new(Texture::from_fn(2, 2, |_, _| Color::new(128, 128, 255))));
+        scene.objects[0] = Box::new(Cube { center: Vec3::zeros(), side_length: 1.0, material, enabled: true });
+        render(&mut framebuffer, &scene, &camera, &light, &RenderSettings::new(), None);
+
+        assert_eq!(framebuffer.buffer, without_map);
+    }
+
+    #[test]
+    fn cube_and_prism_share_one_object_list() {
+        let mut scene = Scene::new();
+        let cube = scene.add_object(Box::new(Cube {
+            center: Vec3::new(-1.5, 0.0, 0.0),
+            side_length: 1.0,
+            material: Material::black(),
+            enabled: true,
+        }));
+        let prism = scene.add_object(Box::new(RectangularPrism {
+            center: Vec3::new(1.5, 0.0, 0.0),
+            width: 1.0,
+            height: 2.0,
+            depth: 1.0,
+            material: Material::black(),
+            enabled: true,
+        }));
+        let camera = Camera::new(Vec3::new(0.0, 0.0, 6.0), Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+        let light = Light::new(Vec3::new(0.0, 5.0, 0.0), Color::new(255, 255, 255), 1.0);
+        let mut settings = RenderSettings::new();
+        settings.record_object_ids = true;
+        let mut framebuffer = Framebuffer::new(60, 40);
+        render(&mut framebuffer, &scene, &camera, &light, &settings, None);
+
+        let id_at = |point: Vec3| {
+            let (screen_x, screen_y) = camera.project(&point, 1.5).unwrap();
+            let x = ((screen_x + 1.0) * 0.5 * 60.0) as usize;
+            let y = ((1.0 - screen_y) * 0.5 * 40.0) as usize;
+            framebuffer.object_ids[y * 60 + x]
+        };
+        assert_eq!(id_at(Vec3::new(-1.5, 0.0, 0.5)), cube as u32);
+        assert_eq!(id_at(Vec3::new(1.5, 0.0, 0.5)), prism as u32);
+        assert_eq!(id_at(Vec3::new(1.5, 0.8, 0.5)), prism as u32); //el prisma es más alto
+        assert_eq!(id_at(Vec3::new(-1.5, 0.8, 0.5)), NO_OBJECT);
+        assert_eq!(id_at(Vec3::new(0.0, 0.0, 0.5)), NO_OBJECT);
+    }
+
+    #[test]
+    fn area_light_gives_a_soft_shadow_edge() {
+        let mut scene = Scene::new();
+        scene.add_object(Box::new(Cube {
+            center: Vec3::new(0.0, 1.0, 0.0),
+            side_length: 1.0,
+            material: Material::black(),
+            enabled: true,
+        }));
+        let mut light = Light::new(Vec3::new(0.0, 4.0, 0.0), Color::new(255, 255, 255), 1.0);
+        light.radius = 0.8;
+        let up = Vec3::new(0.0, 1.0, 0.0);
+
+        //Del centro de la sombra hacia afuera sobre el piso y = 0
+        let visibility: Vec<f32> = (0..30)
+            .map(|i| shadow_visibility(&Vec3::new(i as f32 * 0.1, 0.0, 0.0), &up, &scene, &light, 16, 0.0))
+            .collect();
+        assert_eq!(visibility[0], 0.0);
+        assert_eq!(visibility[29], 1.0);
+        assert!(visibility.iter().filter(|&&v| v > 0.0 && v < 1.0).count() >= 3, "{:?}", visibility);
+
+        //Con una luz puntual el borde es un salto
+        light.radius = 0.0;
+        assert!((0..30).all(|i| {
+            let v = shadow_visibility(&Vec3::new(i as f32 * 0.1, 0.0, 0.0), &up, &scene, &light, 16, 0.0);
+            v == 0.0 || v == 1.0
+        }));
+    }
+
+    #[test]
+    fn disabling_the_front_cube_reveals_the_one_behind() {
+        let mut scene = Scene::new();
+        let front = scene.add_object(Box::new(Cube {
+            center: Vec3::new(0.0, 0.0, 1.0),
+            side_length: 1.0,
+            material: Material::black(),
+            enabled: false,
+        }));
+        let back = scene.add_object(Box::new(Cube {
+            center: Vec3::new(0.0, 0.0, -2.0),
+            side_length: 1.0,
+            material: Material::black(),
+            enabled: true,
+        }));
+        let origin = Vec3::new(0.0, 0.0, 5.0);
+        let direction = Vec3::new(0.0, 0.0, -1.0);
+        assert_eq!(scene.nearest_hit(&origin, &direction).object_id, Some(back));
+
+        scene.objects[front] = Box::new(Cube {
+            center: Vec3::new(0.0, 0.0, 1.0),
+            side_length: 1.0,
+            material: Material::black(),
+            enabled: true,
+        });
+        assert_eq!(scene.nearest_hit(&origin, &direction).object_id, Some(front));
+    }
+
+    #[test]
+    fn numpad_keys_move_the_light_along_each_axis() {
+        let pressed = |keys: &'static [Key]| move |key: Key| keys.contains(&key);
+        assert_eq!(light_offset(pressed(&[]), 0.1), Vec3::zeros());
+        assert_eq!(light_offset(pressed(&[Key::NumPad6]), 0.1), Vec3::new(0.1, 0.0, 0.0));
+        assert_eq!(light_offset(pressed(&[Key::NumPad4, Key::NumPadPlus]), 0.1), Vec3::new(-0.1, 0.1, 0.0));
+        assert_eq!(light_offset(pressed(&[Key::NumPad8]), 0.1), Vec3::new(0.0, 0.0, -0.1));
+        assert_eq!(light_offset(pressed(&[Key::NumPad2, Key::NumPad8]), 0.1), Vec3::zeros()); //se cancelan
+    }
+
+    #[test]
+    fn water_uvs_drift_over_time() {
+        let mut scene = build_scene(None, None);
+        let origin = Vec3::new(-0.9, -0.5, 0.2); //justo sobre el centro del lago
+        let down = Vec3::new(0.0, -1.0, 0.0);
+        let uv_at = |scene: &Scene| {
+            let hit = scene.nearest_hit(&origin, &down);
+            assert_eq!(hit.material.refractive_index, Some(1.33)); //el lago
+            (hit.u, hit.v)
+        };
+
+        scene.update(0.0);
+        let start = uv_at(&scene);
+        scene.update(10.0);
+        let later = uv_at(&scene);
+        assert!((later.0 - start.0).abs() > 0.1 && (later.1 - start.1).abs() > 0.05, "{:?} {:?}", start, later);
+    }
+
+    #[test]
+    fn red_specular_color_tints_the_highlight() {
+        let highlight = |specular_color: Color| {
+            let mut scene = Scene::new();
+            scene.ambient = Color::new(0, 0, 0);
+            let mut material = Material::new(Color::new(0, 0, 0), 50.0, [0.0, 1.0], None, Color::new(0, 0, 0));
+            material.specular_color = specular_color;
+            scene.add_object(Box::new(Cube { center: Vec3::zeros(), side_length: 1.0, material, enabled: true }));
+            let light = Light::new(Vec3::new(0.0, 0.0, 5.0), Color::new(255, 255, 255), 1.0);
+            let origin = Vec3::new(0.0, 0.0, 5.0);
+            cast_ray(&origin, &Vec3::new(0.0, 0.0, -1.0), &scene, &light, &RenderSettings::new(), 0, None).0
+        };
+
+        let white = highlight(Color::new(255, 255, 255));
+        assert!(white.x > 0.5 && (white.x - white.z).abs() < 1e-5);
+        let red = highlight(Color::new(255, 0, 0));
+        assert!(red.x > 0.5, "{:?}", red);
+        assert_eq!((red.y, red.z), (0.0, 0.0));
+    }
+
+    #[test]
+    fn back_facing_surface_keeps_the_ambient() {
+        let (mut scene, _, _) = front_cube();
+        let light = Light::new(Vec3::new(0.0, 0.0, -5.0), Color::new(255, 255, 255), 1.0); //detrás del cubo
+        let origin = Vec3::new(0.0, 0.0, 5.0);
+        let direction = Vec3::new(0.0, 0.0, -1.0);
+        let settings = RenderSettings::new();
+
+        let (lit, _) = cast_ray(&origin, &direction, &scene, &light, &settings, 0, None);
+        assert!(lit.min() > 0.0, "{:?}", lit);
+        scene.ambient = Color::new(0, 0, 0);
+        let (unlit, _) = cast_ray(&origin, &direction, &scene, &light, &settings, 0, None);
+        assert_eq!(unlit, Vec3::zeros());
+    }
+
+    #[test]
+    fn bright_floor_bleeds_its_colour_onto_a_white_wall() {
+        let mut scene = Scene::new();
+        scene.ambient = Color::new(0, 0, 0);
+        let matte = |color: Color| Material::new(color, 1.0, [1.0, 0.0], None, Color::new(0, 0, 0));
+        scene.add_object(Box::new(RectangularPrism {
+            center: Vec3::new(0.0, -0.5, 0.0),
+            width: 10.0,
+            height: 1.0,
+            depth: 10.0,
+            material: matte(Color::new(20, 255, 20)),
+            enabled: true,
+        }));
+        scene.add_object(Box::new(RectangularPrism {
+            center: Vec3::new(-1.5, 2.0, 0.0),
+            width: 1.0,
+            height: 4.0,
+            depth: 10.0,
+            material: matte(Color::new(255, 255, 255)),
+            enabled: true,
+        }));
+        let light = Light::new(Vec3::new(2.0, 6.0, 0.0), Color::new(255, 255, 255), 2.0);
+        //Pared cerca del piso, mirando hacia +x
+        let origin = Vec3::new(3.0, 0.3, 0.0);
+        let direction = Vec3::new(-1.0, 0.0, 0.0);
+        let mut settings = RenderSettings::new();
+
+        let (direct, _) = cast_ray(&origin, &direction, &scene, &light, &settings, 0, None);
+        settings.indirect_samples = 32;
+        let (bounced, _) = cast_ray(&origin, &direction, &scene, &light, &settings, 0, None);
+        assert!((direct.y - direct.x).abs() < 1e-5); //solo la luz blanca
+        assert!(bounced.y > bounced.x * 1.2, "{:?}", bounced);
+    }
+
+    #[test]
+    fn uniform_background_stops_at_the_minimum_samples() {
+        let camera = Camera::new(Vec3::new(0.0, 0.0, 5.0), Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+        let light = Light::new(Vec3::new(0.0, 5.0, 0.0), Color::new(255, 255, 255), 1.0);
+        let mut settings = RenderSettings::new();
+        settings.adaptive_sampling = Some(AdaptiveSampling::new(2, 16, 1e-4));
+        let mut framebuffer = Framebuffer::new(20, 10);
+
+        let rays = render(&mut framebuffer, &Scene::new(), &camera, &light, &settings, None);
+        assert_eq!(rays, 20 * 10 * 2);
+        //Dos muestras distintas (0 y 1) no alcanzan
+        let adaptive = settings.adaptive_sampling.unwrap();
+        assert!(!adaptive.converged(2, 1.0, 1.0));
+        assert!(adaptive.converged(2, 1.0, 0.5));
+    }
+
+    #[test]
+    fn exposure_two_doubles_a_mid_colour() {
+        let (scene, camera, mut light) = front_cube();
+        light.intensity = 0.4;
+        let mut settings = RenderSettings::new();
+        settings.tone_mapping = ToneMapping::Clamp;
+        let mut framebuffer = Framebuffer::new(40, 30);
+        let center = 15 * 40 + 20;
+
+        render(&mut framebuffer, &scene, &camera, &light, &settings, None);
+        let (hdr, channel) = (framebuffer.hdr[center], framebuffer.buffer[center] & 0xFF);
+        assert!(hdr.z > 0.1 && hdr.z < 0.5, "{:?}", hdr);
+
+        settings.exposure = 2.0;
+        render(&mut framebuffer, &scene, &camera, &light, &settings, None);
+        assert!((framebuffer.hdr[center] - hdr * 2.0).magnitude() < 1e-5);
+        assert!(((framebuffer.buffer[center] & 0xFF) as i32 - 2 * channel as i32).abs() <= 1);
+    }
+
+    #[test]
+    fn light_behind_the_surface_gives_no_highlight() {
+        let mut scene = Scene::new();
+        scene.ambient = Color::new(0, 0, 0);
+        scene.shadow_bias = 0.5; //el rayo de sombra sale del disco antes de cruzar su plano
+        scene.add_object(Box::new(Disk {
+            center: Vec3::zeros(),
+            normal: Vec3::new(0.0, 1.0, 0.0),
+            radius: 1.0,
+            material: Material::new(Color::new(0, 0, 0), 10.0, [0.0, 1.0], None, Color::new(0, 0, 0)),
+            enabled: true,
+        }));
+        //Vista rasante casi en el reflejo de la luz
+        let origin = Vec3::new(-10.0, 1.0, 0.0);
+        let direction = (-origin).normalize();
+        let highlight = |light_y: f32| {
+            let light = Light::new(Vec3::new(10.0, light_y, 0.0), Color::new(255, 255, 255), 1.0);
+            cast_ray(&origin, &direction, &scene, &light, &RenderSettings::new(), 0, None).0
+        };
+
+        assert!(highlight(0.5).x > 0.5); //luz delante: brillo
+        assert_eq!(highlight(-0.5), Vec3::zeros()); //luz detrás: nada
+    }
+
+    #[test]
+    fn matte_material_looks_the_same_from_the_highlight_angle() {
+        let shade_from = |specular_albedo: f32, eye: Vec3| {
+            let mut scene = Scene::new();
+            scene.add_object(Box::new(Disk {
+                center: Vec3::zeros(),
+                normal: Vec3::new(0.0, 1.0, 0.0),
+                radius: 1.0,
+                material: Material::new(Color::new(120, 160, 90), 30.0, [0.8, specular_albedo], None, Color::new(0, 0, 0)),
+                enabled: true,
+            }));
+            let light = Light::new(Vec3::new(3.0, 3.0, 0.0), Color::new(255, 255, 255), 1.0);
+            cast_ray(&eye, &(-eye).normalize(), &scene, &light, &RenderSettings::new(), 0, None).0
+        };
+        let mirror = Vec3::new(-3.0, 3.0, 0.0); //en el reflejo de la luz
+        let side = Vec3::new(0.0, 3.0, 3.0);
+
+        assert!((shade_from(0.5, mirror) - shade_from(0.5, side)).magnitude() > 0.1);
+        assert_eq!(shade_from(0.0, mirror), shade_from(0.0, side));
+    }
+}
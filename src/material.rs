@@ -1,11 +1,125 @@
+use nalgebra_glm::Vec3;
 use crate::color::Color;
+use crate::ripples::Ripples;
 use image::GenericImageView;
+use std::collections::HashMap;
+use std::sync::Arc;
 
 #[derive(Debug, Clone)]
 pub struct Texture {
     pub data: Vec<u8>,
     pub width: usize,
     pub height: usize,
+    pub checker: Option<Checker>,
+}
+
+//Tablero de ajedrez calculado a partir de las uvs
+#[derive(Debug, Clone)]
+pub struct Checker {
+    pub colors: [[u8; 4]; 2],
+    pub scale: f32, //casillas por unidad de uv
+}
+
+impl Texture {
+    //Texel RGBA en coordenadas u, v
+    //None si data no alcanza para width x height en RGBA8 (quien llama usa el color difuso)
+    pub fn sample(&self, u: f32, v: f32) -> Option<[u8; 4]> {
+        if let Some(checker) = self.checker.as_ref() {
+            let cell = (u * checker.scale).floor() as i64 + (v * checker.scale).floor() as i64;
+            return Some(checker.colors[cell.rem_euclid(2) as usize]);
+        }
+        if self.width == 0 || self.height == 0 {
+            return None;
+        }
+
+        let texture_x = (u * self.width as f32).clamp(0.0, (self.width - 1) as f32) as usize;
+        let texture_y = (v * self.height as f32).clamp(0.0, (self.height - 1) as f32) as usize;
+        let texture_index = (texture_y * self.width + texture_x) * 4;
+
+        let texel = self.data.get(texture_index..texture_index + 4)?;
+        Some([texel[0], texel[1], texel[2], texel[3]])
+    }
+
+    // Textura procedural; data guarda un tablero 2x2 como referencia
+    pub fn checker(c1: Color, c2: Color, scale: f32) -> Self {
+        let colors = [to_rgba(c1), to_rgba(c2)];
+        let data = [colors[0], colors[1], colors[1], colors[0]].concat();
+
+        Texture {
+            data,
+            width: 2,
+            height: 2,
+            checker: Some(Checker { colors, scale }),
+        }
+    }
+
+    // Color promedio de todos los texels (p. ej. tinte ambiental a partir del cielo)
+    pub fn average_color(&self) -> Color {
+        if let Some(checker) = self.checker.as_ref() {
+            let [a, b] = checker.colors;
+            let mean = |i: usize| (a[i] as f32 + b[i] as f32) / (2.0 * 255.0);
+            return Color::from_rgb_f32(mean(0), mean(1), mean(2));
+        }
+
+        let texels = self.data.chunks_exact(4);
+        let count = texels.len().max(1) as f32;
+        let sum = texels.fold(Vec3::zeros(), |sum, texel| {
+            sum + Vec3::new(texel[0] as f32, texel[1] as f32, texel[2] as f32)
+        });
+        Color::from_vec3(&(sum / (count * 255.0)))
+    }
+
+    // Tablero magenta/negro para texturas que no se pudieron cargar
+    pub fn missing() -> Self {
+        Texture::checker(Color::new(255, 0, 255), Color::new(0, 0, 0), 8.0)
+    }
+
+    // Textura procedural: `pixel(x, y)` da el color de cada texel
+    pub fn from_fn(width: usize, height: usize, pixel: impl Fn(usize, usize) -> Color) -> Self {
+        let data = (0..width * height)
+            .flat_map(|i| to_rgba(pixel(i % width, i / width)))
+            .collect();
+
+        Texture {
+            data,
+            width,
+            height,
+            checker: None,
+        }
+    }
+}
+
+fn to_rgba(color: Color) -> [u8; 4] {
+    let hex = color.to_hex();
+    [(hex >> 16) as u8, (hex >> 8) as u8, hex as u8, 255]
+}
+
+//Cómo se resuelven uvs fuera de [0, 1]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WrapMode {
+    Clamp,
+    Repeat,
+    Mirror,
+}
+
+impl WrapMode {
+    pub fn apply(self, t: f32) -> f32 {
+        match self {
+            WrapMode::Clamp => t.clamp(0.0, 1.0),
+            WrapMode::Repeat => t.rem_euclid(1.0),
+            WrapMode::Mirror => {
+                let t = t.rem_euclid(2.0);
+                if t > 1.0 { 2.0 - t } else { t }
+            }
+        }
+    }
+}
+
+// Metales tiñen sus reflejos con su color, dieléctricos reflejan blanco
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MaterialKind {
+    Dielectric,
+    Metal,
 }
 
 #[derive(Debug, Clone)]
@@ -13,18 +127,42 @@ pub struct Material {
     pub diffuse: Color,
     pub specular: f32,
     pub albedo: [f32; 2],
-    pub texture: Option<Texture>,
-    pub emission: Color
+    pub texture: Option<Arc<Texture>>,
+    pub emission: Vec3, //RGB en flotante, puede pasar de 1 (HDR, para bloom)
+    pub emission_texture: Option<Arc<Texture>>, //si está, se multiplica por emission
+    pub normal_map: Option<Arc<Texture>>,
+    pub uv_scale: [f32; 2],
+    pub wrap_mode: WrapMode,
+    pub refractive_index: Option<f32>,
+    pub emission_strength: f32,
+    pub uv_offset: [f32; 2],
+    pub uv_scroll: [f32; 2], //desplazamiento de uvs por segundo
+    pub texture_rotation: f32, //radianes, alrededor de (0.5, 0.5)
+    pub specular_color: Color, //filtra el color de la luz en los brillos
+    pub kind: MaterialKind,
+    pub ripples: Option<Ripples>, //olas animadas en la normal (agua)
 }
 
 impl Material {
-    pub fn new(diffuse: Color, specular: f32, albedo: [f32; 2], texture: Option<Texture>, emission: Color) -> Self {
+    pub fn new(diffuse: Color, specular: f32, albedo: [f32; 2], texture: Option<Arc<Texture>>, emission: Color) -> Self {
         Material {
             diffuse,
             specular,
             albedo,
             texture,
-            emission
+            emission: emission.to_vec3(),
+            emission_texture: None,
+            normal_map: None,
+            uv_scale: [1.0, 1.0],
+            wrap_mode: WrapMode::Clamp,
+            refractive_index: None,
+            emission_strength: 1.0,
+            uv_offset: [0.0, 0.0],
+            uv_scroll: [0.0, 0.0],
+            texture_rotation: 0.0,
+            specular_color: Color::new(255, 255, 255),
+            kind: MaterialKind::Dielectric,
+            ripples: None,
         }
     }
 
@@ -34,27 +172,471 @@ impl Material {
             specular: 0.0,
             albedo: [0.0, 0.0],
             texture: None,
-            emission: Color::new(0, 0, 0) //aun no tiene emisison
+            emission: Vec3::zeros(), //aun no tiene emisison
+            emission_texture: None,
+            normal_map: None,
+            uv_scale: [1.0, 1.0],
+            wrap_mode: WrapMode::Clamp,
+            refractive_index: None,
+            emission_strength: 1.0,
+            uv_offset: [0.0, 0.0],
+            uv_scroll: [0.0, 0.0],
+            texture_rotation: 0.0,
+            specular_color: Color::new(255, 255, 255),
+            kind: MaterialKind::Dielectric,
+            ripples: None,
+        }
+    }
+
+    // Escala, desplaza, rota y luego resuelve uvs fuera de rango según wrap_mode
+    pub fn resolve_uv(&self, u: f32, v: f32) -> (f32, f32) {
+        let u = u * self.uv_scale[0] + self.uv_offset[0];
+        let v = v * self.uv_scale[1] + self.uv_offset[1];
+        let (u, v) = rotate_uv(u, v, self.texture_rotation);
+        (self.wrap_mode.apply(u), self.wrap_mode.apply(v))
+    }
+
+    // Derivadas dp/du y dp/dv de la primitiva llevadas a las uvs de resolve_uv (escala y giro)
+    pub fn transform_uv_derivatives(&self, dp_du: &Vec3, dp_dv: &Vec3) -> (Vec3, Vec3) {
+        let (sin, cos) = self.texture_rotation.sin_cos();
+        let (scale_u, scale_v) = (self.uv_scale[0], self.uv_scale[1]);
+        (
+            dp_du * (cos / scale_u) - dp_dv * (sin / scale_v),
+            dp_du * (sin / scale_u) + dp_dv * (cos / scale_v),
+        )
+    }
+
+    // Color emitido en (u, v): emission modulado por la textura de emisión si hay
+    pub fn emission_at(&self, u: f32, v: f32) -> Vec3 {
+        let emission = match self.emission_texture.as_ref().and_then(|texture| texture.sample(u, v)) {
+            Some(texel) => self.emission.component_mul(&Color::new(texel[0], texel[1], texel[2]).to_vec3()),
+            None => self.emission,
+        };
+        emission * self.emission_strength
+    }
+
+    // Animación: mover las uvs según el tiempo transcurrido
+    pub fn update(&mut self, time: f32) {
+        self.uv_offset = [self.uv_scroll[0] * time, self.uv_scroll[1] * time];
+        if let Some(ripples) = self.ripples.as_mut() {
+            ripples.time = time;
+        }
+    }
+
+    // Aproximación de Schlick, cos_theta entre vista y normal
+    pub fn reflectivity(&self, cos_theta: f32) -> f32 {
+        match self.refractive_index {
+            Some(n) => {
+                let r0 = ((1.0 - n) / (1.0 + n)).powi(2);
+                r0 + (1.0 - r0) * (1.0 - cos_theta.clamp(0.0, 1.0)).powi(5)
+            }
+            None => 0.0,
+        }
+    }
+
+    // Filtro que se aplica al color reflejado
+    pub fn reflection_tint(&self) -> Vec3 {
+        match self.kind {
+            MaterialKind::Metal => self.diffuse.to_vec3(),
+            MaterialKind::Dielectric => Vec3::new(1.0, 1.0, 1.0),
         }
     }
 
+    pub fn builder() -> MaterialBuilder {
+        MaterialBuilder::new()
+    }
+
     //Cargar textura
-    pub fn load_texture(path: &str) -> Option<Texture> {
-        match image::open(path) {
-            Ok(img) => {
-                let (width, height) = img.dimensions();
-                let img = img.to_rgba8();
-                let data = img.into_raw();
-                Some(Texture {
-                    data,
-                    width: width as usize,
-                    height: height as usize,
-                })
-            },
-            Err(e) => {
-                println!("Error al cargar la textura {}: {:?}", path, e);
-                None
+    pub fn load_texture(path: &str) -> Result<Texture, image::ImageError> {
+        Material::load_texture_with_max_size(path, None)
+    }
+
+    //Con max_size las imágenes más grandes se reducen (sin deformarse) al cargarlas
+    //El error se devuelve: quien llama decide si avisar o usar otra textura
+    pub fn load_texture_with_max_size(path: &str, max_size: Option<u32>) -> Result<Texture, image::ImageError> {
+        let img = image::open(path)?;
+        let img = match max_size {
+            Some(max) if img.width() > max || img.height() > max => {
+                img.resize(max.max(1), max.max(1), image::imageops::FilterType::Triangle)
             }
+            _ => img,
+        };
+        let (width, height) = img.dimensions();
+        let data = img.to_rgba8().into_raw();
+        Ok(Texture {
+            data,
+            width: width as usize,
+            height: height as usize,
+            checker: None,
+        })
+    }
+}
+
+// Exponente de Phong más alto que se obtiene con roughness 0
+const MAX_SPECULAR_EXPONENT: f32 = 256.0;
+
+// Rugosidad en [0, 1] a exponente de Phong: 0 = brillo puntual, 1 = brillo muy abierto
+pub fn roughness_to_specular(roughness: f32) -> f32 {
+    let smoothness = 1.0 - roughness.clamp(0.0, 1.0);
+    (smoothness * smoothness * MAX_SPECULAR_EXPONENT).max(1.0)
+}
+
+// Girar (u, v) alrededor del centro de la textura
+fn rotate_uv(u: f32, v: f32, angle: f32) -> (f32, f32) {
+    if angle == 0.0 {
+        return (u, v);
+    }
+    let (sin, cos) = angle.sin_cos();
+    let (du, dv) = (u - 0.5, v - 0.5);
+    (0.5 + du * cos - dv * sin, 0.5 + du * sin + dv * cos)
+}
+
+//Valores por defecto: blanco mate, sin textura ni emisión
+#[derive(Clone)]
+pub struct MaterialBuilder {
+    diffuse: Color,
+    specular: f32,
+    albedo: [f32; 2],
+    texture: Option<Arc<Texture>>,
+    texture_path: Option<String>,
+    max_texture_size: Option<u32>,
+    emission: Vec3,
+    emission_texture: Option<Arc<Texture>>,
+    emission_strength: f32,
+    uv_scale: [f32; 2],
+    wrap_mode: WrapMode,
+    texture_rotation: f32,
+    specular_color: Color,
+    kind: MaterialKind,
+    refractive_index: Option<f32>,
+    ripples: Option<Ripples>,
+}
+
+impl MaterialBuilder {
+    pub fn new() -> Self {
+        MaterialBuilder {
+            diffuse: Color::new(255, 255, 255),
+            specular: 0.0,
+            albedo: [1.0, 0.0],
+            texture: None,
+            texture_path: None,
+            max_texture_size: None,
+            emission: Vec3::zeros(),
+            emission_texture: None,
+            emission_strength: 1.0,
+            uv_scale: [1.0, 1.0],
+            wrap_mode: WrapMode::Clamp,
+            texture_rotation: 0.0,
+            specular_color: Color::new(255, 255, 255),
+            kind: MaterialKind::Dielectric,
+            refractive_index: None,
+            ripples: None,
+        }
+    }
+
+    pub fn diffuse(mut self, diffuse: Color) -> Self {
+        self.diffuse = diffuse;
+        self
+    }
+
+    pub fn specular(mut self, specular: f32) -> Self {
+        self.specular = specular;
+        self
+    }
+
+    // Alternativa a specular: reemplaza el exponente
+    pub fn roughness(mut self, roughness: f32) -> Self {
+        self.specular = roughness_to_specular(roughness);
+        self
+    }
+
+    pub fn albedo(mut self, albedo: [f32; 2]) -> Self {
+        self.albedo = albedo;
+        self
+    }
+
+    //Reemplaza a texture_path
+    pub fn texture(mut self, texture: Option<Arc<Texture>>) -> Self {
+        self.texture = texture;
+        self.texture_path = None;
+        self
+    }
+
+    //La textura se carga en build()
+    pub fn texture_path(mut self, path: &str) -> Self {
+        self.texture_path = Some(path.to_string());
+        self
+    }
+
+    //Lado máximo de la textura de texture_path, como TextureCache::max_texture_size
+    pub fn max_texture_size(mut self, max_texture_size: Option<u32>) -> Self {
+        self.max_texture_size = max_texture_size;
+        self
+    }
+
+    pub fn emission(mut self, emission: Color) -> Self {
+        self.emission = emission.to_vec3();
+        self
+    }
+
+    //Emisión en flotante: componentes mayores a 1 no se recortan
+    pub fn emission_hdr(mut self, emission: Vec3) -> Self {
+        self.emission = emission;
+        self
+    }
+
+    pub fn emission_texture(mut self, emission_texture: Option<Arc<Texture>>) -> Self {
+        self.emission_texture = emission_texture;
+        self
+    }
+
+    pub fn emission_strength(mut self, emission_strength: f32) -> Self {
+        self.emission_strength = emission_strength;
+        self
+    }
+
+    pub fn uv_scale(mut self, uv_scale: [f32; 2]) -> Self {
+        self.uv_scale = uv_scale;
+        self
+    }
+
+    pub fn wrap_mode(mut self, wrap_mode: WrapMode) -> Self {
+        self.wrap_mode = wrap_mode;
+        self
+    }
+
+    pub fn texture_rotation(mut self, texture_rotation: f32) -> Self {
+        self.texture_rotation = texture_rotation;
+        self
+    }
+
+    pub fn specular_color(mut self, specular_color: Color) -> Self {
+        self.specular_color = specular_color;
+        self
+    }
+
+    pub fn kind(mut self, kind: MaterialKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    // Necesario para que haya reflejos (Fresnel)
+    pub fn refractive_index(mut self, refractive_index: f32) -> Self {
+        self.refractive_index = Some(refractive_index);
+        self
+    }
+
+    pub fn ripples(mut self, ripples: Ripples) -> Self {
+        self.ripples = Some(ripples);
+        self
+    }
+
+    //Solo falla si texture_path no se puede cargar
+    pub fn build(self) -> Result<Material, image::ImageError> {
+        let texture = match self.texture_path {
+            Some(path) => Some(Arc::new(Material::load_texture_with_max_size(&path, self.max_texture_size)?)),
+            None => self.texture,
+        };
+        let mut material = Material::new(self.diffuse, self.specular, self.albedo, texture, Color::new(0, 0, 0));
+        material.emission = self.emission;
+        material.emission_texture = self.emission_texture;
+        material.emission_strength = self.emission_strength;
+        material.uv_scale = self.uv_scale;
+        material.wrap_mode = self.wrap_mode;
+        material.texture_rotation = self.texture_rotation;
+        material.specular_color = self.specular_color;
+        material.kind = self.kind;
+        material.refractive_index = self.refractive_index;
+        material.ripples = self.ripples;
+        Ok(material)
+    }
+}
+
+//Texturas compartidas por ruta, cada archivo se decodifica una sola vez
+pub struct TextureCache {
+    textures: HashMap<String, Arc<Texture>>,
+    pub max_texture_size: Option<u32>, //lado máximo en pixeles, None = tamaño original
+}
+
+impl TextureCache {
+    pub fn new() -> Self {
+        TextureCache {
+            textures: HashMap::new(),
+            max_texture_size: None,
+        }
+    }
+
+    pub fn load(&mut self, path: &str) -> Result<Arc<Texture>, image::ImageError> {
+        if let Some(texture) = self.textures.get(path) {
+            return Ok(Arc::clone(texture));
+        }
+
+        let texture = Arc::new(Material::load_texture_with_max_size(path, self.max_texture_size)?);
+        self.textures.insert(path.to_string(), Arc::clone(&texture));
+        Ok(texture)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Columna x con rojo = 60 * x, para saber qué texel se leyó
+    fn columns() -> Texture {
+        Texture::from_fn(4, 1, |x, _| Color::new(60 * x as u8, 0, 0))
+    }
+
+    fn sampled_column(wrap_mode: WrapMode, u: f32) -> u8 {
+        let mut material = Material::black();
+        material.wrap_mode = wrap_mode;
+        let (u, v) = material.resolve_uv(u, 0.5);
+        columns().sample(u, v).unwrap()[0] / 60
+    }
+
+    #[test]
+    fn adjacent_checker_cells_alternate() {
+        let (white, black) = (Color::new(255, 255, 255), Color::new(0, 0, 0));
+        let texture = Texture::checker(white, black, 4.0); //casillas de 0.25
+        let at = |u: f32, v: f32| texture.sample(u, v).unwrap();
+        for (u, v) in [(0.1, 0.1), (0.6, 0.35), (0.9, 0.85)] {
+            assert_ne!(at(u, v), at(u + 0.25, v));
+            assert_ne!(at(u, v), at(u, v + 0.25));
+            assert_eq!(at(u, v), at(u + 0.25, v + 0.25));
         }
-    }    
-}
\ No newline at end of file
+        assert_eq!(at(0.1, 0.1), [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn half_black_half_white_averages_to_mid_gray() {
+        let texture = Texture::from_fn(4, 2, |_, y| if y == 0 { Color::new(0, 0, 0) } else { Color::new(255, 255, 255) });
+        assert_eq!(texture.average_color().to_hex(), 0x808080);
+        let checker = Texture::checker(Color::new(0, 0, 0), Color::new(255, 255, 255), 4.0);
+        assert_eq!(checker.average_color().to_hex(), 0x808080);
+    }
+
+    #[test]
+    fn builder_with_only_diffuse_uses_the_defaults() {
+        let material = Material::builder().diffuse(Color::new(10, 20, 30)).build().unwrap();
+        assert_eq!(material.diffuse.to_hex(), 0x0A141E);
+        assert_eq!(material.specular, 0.0);
+        assert_eq!(material.albedo, [1.0, 0.0]);
+        assert!(material.texture.is_none());
+        assert_eq!(material.emission, Vec3::zeros());
+        assert_eq!(material.uv_scale, [1.0, 1.0]);
+        assert_eq!(material.wrap_mode, WrapMode::Clamp);
+        assert_eq!(material.kind, MaterialKind::Dielectric);
+        assert_eq!(material.refractive_index, None);
+    }
+
+    #[test]
+    fn builder_returns_the_texture_error() {
+        let result = Material::builder().texture_path("textures/no_existe.png").build();
+        assert!(result.is_err());
+        //texture() reemplaza la ruta: ya no hay nada que cargar
+        let builder = Material::builder().texture_path("textures/no_existe.png");
+        assert!(builder.texture(None).build().is_ok());
+    }
+
+    #[test]
+    fn wrap_modes_at_u_1_5() {
+        assert_eq!(sampled_column(WrapMode::Clamp, 1.5), 3); //borde derecho
+        assert_eq!(sampled_column(WrapMode::Repeat, 1.5), 2); //1.5 -> 0.5
+        assert_eq!(sampled_column(WrapMode::Mirror, 1.5), 2); //1.5 -> 0.5 reflejado
+    }
+
+    #[test]
+    fn repeat_and_mirror_differ_off_center() {
+        assert_eq!(sampled_column(WrapMode::Repeat, 1.25), 1); //0.25
+        assert_eq!(sampled_column(WrapMode::Mirror, 1.25), 3); //0.75
+    }
+
+    #[test]
+    fn quarter_turn_swaps_u_and_v() {
+        let texture = Texture::from_fn(4, 4, |x, _| Color::new(60 * x as u8, 0, 0)); //cambia solo con u
+        let red_at = |material: &Material, u: f32, v: f32| {
+            let (u, v) = material.resolve_uv(u, v);
+            texture.sample(u, v).unwrap()[0]
+        };
+        let mut material = Material::black();
+        assert_ne!(red_at(&material, 0.1, 0.4), red_at(&material, 0.9, 0.4));
+        assert_eq!(red_at(&material, 0.4, 0.1), red_at(&material, 0.4, 0.9));
+
+        material.texture_rotation = std::f32::consts::FRAC_PI_2;
+        assert_eq!(red_at(&material, 0.1, 0.4), red_at(&material, 0.9, 0.4));
+        assert_ne!(red_at(&material, 0.4, 0.1), red_at(&material, 0.4, 0.9));
+    }
+
+    #[test]
+    fn low_roughness_gives_a_sharper_highlight() {
+        assert!(roughness_to_specular(0.0) > roughness_to_specular(1.0));
+        assert!(roughness_to_specular(0.2) > roughness_to_specular(0.8));
+        assert_eq!(roughness_to_specular(-1.0), roughness_to_specular(0.0)); //se recorta a [0, 1]
+        let material = Material::builder().roughness(0.0).build().unwrap();
+        assert_eq!(material.specular, roughness_to_specular(0.0));
+    }
+
+    #[test]
+    fn cache_returns_the_same_texture_for_the_same_path() {
+        let mut cache = TextureCache::new();
+        let first = cache.load("textures/wood.png").unwrap();
+        let second = cache.load("textures/wood.png").unwrap();
+        let other = cache.load("textures/wall.png").unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(!Arc::ptr_eq(&first, &other));
+        assert!(cache.load("textures/no_existe.png").is_err());
+    }
+
+    #[test]
+    fn water_reflects_more_at_grazing_angles() {
+        let mut water = Material::black();
+        water.refractive_index = Some(1.33);
+        let head_on = water.reflectivity(1.0);
+        let grazing = water.reflectivity(0.05);
+        assert!((head_on - 0.02).abs() < 1e-3); //((1 - 1.33) / (1 + 1.33))^2
+        assert!(grazing > head_on * 10.0);
+        assert_eq!(Material::black().reflectivity(0.05), 0.0); //sin índice no refleja
+    }
+
+    #[test]
+    fn doubling_emission_strength_doubles_the_emission() {
+        let mut material = Material::black();
+        material.emission = Vec3::new(0.4, 0.3, 0.1);
+        let single = material.emission_at(0.5, 0.5);
+        material.emission_strength = 2.0;
+        assert!((material.emission_at(0.5, 0.5) - single * 2.0).magnitude() < 1e-6);
+    }
+
+    #[test]
+    fn two_tone_emission_texture_glows_unevenly() {
+        let mut material = Material::black();
+        material.emission = Vec3::new(2.0, 2.0, 2.0);
+        //mitad izquierda encendida, mitad derecha apagada
+        let glow = Texture::from_fn(2, 1, |x, _| if x == 0 { Color::new(255, 255, 255) } else { Color::new(0, 0, 0) });
+        material.emission_texture = Some(Arc::new(glow));
+
+        assert_eq!(material.emission_at(0.25, 0.5), Vec3::new(2.0, 2.0, 2.0));
+        assert_eq!(material.emission_at(0.75, 0.5), Vec3::zeros());
+        material.emission_texture = None;
+        assert_eq!(material.emission_at(0.75, 0.5), Vec3::new(2.0, 2.0, 2.0)); //sin textura, el color plano
+    }
+
+    #[test]
+    fn sampling_the_far_corner_does_not_panic() {
+        let texture = Texture::from_fn(3, 2, |x, y| Color::new(40 * x as u8, 100 * y as u8, 7));
+        assert_eq!(texture.sample(1.0, 1.0), Some([80, 100, 7, 255]));
+        assert_eq!(texture.sample(1.0, 0.0), Some([80, 0, 7, 255]));
+
+        //Datos incompletos: sin color en lugar de pánico
+        let truncated = Texture { data: vec![0; 5 * 4], width: 3, height: 2, checker: None };
+        assert_eq!(truncated.sample(1.0, 1.0), None);
+    }
+
+    #[test]
+    fn max_texture_size_shrinks_large_textures() {
+        let full = Material::load_texture_with_max_size("textures/moon.png", None).unwrap();
+        assert!(full.width.max(full.height) > 64);
+        let small = Material::load_texture_with_max_size("textures/moon.png", Some(64)).unwrap();
+        assert!(small.width <= 64 && small.height <= 64, "{}x{}", small.width, small.height);
+        assert_eq!(small.data.len(), small.width * small.height * 4);
+    }
+}
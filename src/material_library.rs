@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use crate::color::Color;
+use crate::material::{roughness_to_specular, Material, MaterialKind, TextureCache, WrapMode};
+use crate::toml_lite::{self, parse_array, parse_f32, parse_string};
+
+#[derive(Debug)]
+pub enum MaterialLibraryError {
+    Io(std::io::Error),
+    Parse { material: String, message: String },
+    MissingTexture { material: String, path: String },
+}
+
+impl fmt::Display for MaterialLibraryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MaterialLibraryError::Io(e) => write!(f, "Error al leer la biblioteca de materiales: {}", e),
+            MaterialLibraryError::Parse { material, message } => write!(f, "Material '{}': {}", material, message),
+            MaterialLibraryError::MissingTexture { material, path } => {
+                write!(f, "Material '{}': no se pudo cargar la textura {}", material, path)
+            }
+        }
+    }
+}
+
+impl From<std::io::Error> for MaterialLibraryError {
+    fn from(e: std::io::Error) -> Self {
+        MaterialLibraryError::Io(e)
+    }
+}
+
+// Materiales con nombre definidos en un archivo, una sección por material:
+//
+// [madera]
+// diffuse = [101, 62, 4]
+// specular = 20.0 (o roughness = 0.7, de 0 a 1)
+// albedo = [0.6, 0.2]
+// texture = "textures/wood.png"
+// emission = [0, 0, 0]
+// emission_strength = 1.0 (multiplica emission, mayor a 1 pasa de 255)
+// refractive_index = 1.5 (con esto refleja)
+// kind = "metal" (tiñe los reflejos con diffuse) o "dielectric"
+// uv_scale = [4.0, 4.0]
+// wrap_mode = "repeat" (o "clamp", "mirror")
+//
+// Solo diffuse es obligatorio
+pub struct MaterialLibrary {
+    materials: HashMap<String, Material>,
+}
+
+impl MaterialLibrary {
+    pub fn load(path: &str, textures: &mut TextureCache) -> Result<Self, MaterialLibraryError> {
+        let source = fs::read_to_string(path)?;
+        Self::parse(&source, textures)
+    }
+
+    pub fn parse(source: &str, textures: &mut TextureCache) -> Result<Self, MaterialLibraryError> {
+        let sections = toml_lite::parse(source).map_err(|message| MaterialLibraryError::Parse {
+            material: String::new(),
+            message,
+        })?;
+
+        let mut materials = HashMap::new();
+        for (name, values) in sections.iter().filter(|(name, _)| !name.is_empty()) {
+            let parse_error = |message: String| MaterialLibraryError::Parse { material: name.clone(), message };
+            let diffuse = parse_color("diffuse", toml_lite::get(&sections, name, "diffuse").map_err(parse_error)?).map_err(parse_error)?;
+            let specular = match values.get("roughness") {
+                Some(value) => roughness_to_specular(parse_f32(value).map_err(parse_error)?),
+                None => values.get("specular").map_or(Ok(0.0), |v| parse_f32(v)).map_err(parse_error)?,
+            };
+            let albedo = values.get("albedo").map_or(Ok([1.0, 0.0]), |v| parse_array::<2>(v)).map_err(parse_error)?;
+            let emission = values.get("emission").map_or(Ok(Color::new(0, 0, 0)), |v| parse_color("emission", v)).map_err(parse_error)?;
+            let emission_strength = values.get("emission_strength").map_or(Ok(1.0), |v| parse_f32(v)).map_err(parse_error)?;
+            let refractive_index = values.get("refractive_index").map(|v| parse_f32(v)).transpose().map_err(parse_error)?;
+            let uv_scale = values.get("uv_scale").map_or(Ok([1.0, 1.0]), |v| parse_array::<2>(v)).map_err(parse_error)?;
+            let wrap_mode = match values.get("wrap_mode").map(|v| parse_string(v)).transpose().map_err(parse_error)?.as_deref() {
+                None | Some("clamp") => WrapMode::Clamp,
+                Some("repeat") => WrapMode::Repeat,
+                Some("mirror") => WrapMode::Mirror,
+                Some(other) => return Err(parse_error(format!("wrap_mode debe ser clamp, repeat o mirror: '{}'", other))),
+            };
+            let kind = match values.get("kind").map(|v| parse_string(v)).transpose().map_err(parse_error)?.as_deref() {
+                None | Some("dielectric") => MaterialKind::Dielectric,
+                Some("metal") => MaterialKind::Metal,
+                Some(other) => return Err(parse_error(format!("kind debe ser metal o dielectric: '{}'", other))),
+            };
+
+            let texture = match values.get("texture") {
+                Some(value) => {
+                    let path = parse_string(value).map_err(parse_error)?;
+                    let texture = textures.load(&path).map_err(|_| MaterialLibraryError::MissingTexture {
+                        material: name.clone(),
+                        path,
+                    })?;
+                    Some(texture)
+                }
+                None => None,
+            };
+
+            let mut builder = Material::builder()
+                .diffuse(diffuse)
+                .specular(specular)
+                .albedo(albedo)
+                .texture(texture)
+                .emission(emission)
+                .emission_strength(emission_strength)
+                .uv_scale(uv_scale)
+                .wrap_mode(wrap_mode)
+                .kind(kind);
+            if let Some(refractive_index) = refractive_index {
+                builder = builder.refractive_index(refractive_index);
+            }
+            let material = builder.build().expect("sin texture_path no se carga nada");
+            materials.insert(name.clone(), material);
+        }
+
+        Ok(MaterialLibrary { materials })
+    }
+
+    pub fn get(&self, name: &str) -> Option<Material> {
+        self.materials.get(name).cloned()
+    }
+}
+
+// [r, g, b] con componentes enteros de 0 a 255
+fn parse_color(key: &str, value: &str) -> Result<Color, String> {
+    let components = parse_array::<3>(value)?;
+    if components.iter().any(|c| c.fract() != 0.0 || !(0.0..=255.0).contains(c)) {
+        return Err(format!("{} debe tener componentes enteros de 0 a 255: '{}'", key, value));
+    }
+    let [r, g, b] = components;
+    Ok(Color::new(r as u8, g as u8, b as u8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn out_of_range_color_is_an_error() {
+        for source in ["[m]\ndiffuse = [300, 0, 0]", "[m]\ndiffuse = [0, -5, 0]"] {
+            match MaterialLibrary::parse(source, &mut TextureCache::new()) {
+                Err(MaterialLibraryError::Parse { message, .. }) => assert!(message.contains("diffuse"), "{}", message),
+                _ => panic!("se esperaba un error de diffuse"),
+            }
+        }
+    }
+
+    #[test]
+    fn emission_strength_goes_past_255() {
+        let source = "[farol]\ndiffuse = [255, 200, 120]\nemission = [255, 200, 120]\nemission_strength = 3.0";
+        let library = MaterialLibrary::parse(source, &mut TextureCache::new()).unwrap();
+        let emission = library.get("farol").unwrap().emission_at(0.5, 0.5);
+        assert!((emission - Color::new(255, 200, 120).to_vec3() * 3.0).magnitude() < 1e-5);
+        assert!(emission.x > 1.0);
+    }
+
+    #[test]
+    fn kind_and_refractive_index_are_parsed() {
+        let source = "[cobre]\ndiffuse = [184, 115, 51]\nkind = \"metal\"\nrefractive_index = 1.5\n\n[yeso]\ndiffuse = [240, 240, 240]";
+        let library = MaterialLibrary::parse(source, &mut TextureCache::new()).unwrap();
+        let copper = library.get("cobre").unwrap();
+        assert_eq!(copper.kind, MaterialKind::Metal);
+        assert_eq!(copper.refractive_index, Some(1.5));
+        assert_eq!(library.get("yeso").unwrap().kind, MaterialKind::Dielectric);
+        assert!(MaterialLibrary::parse("[m]\ndiffuse = [1, 2, 3]\nkind = \"vidrio\"", &mut TextureCache::new()).is_err());
+    }
+
+    #[test]
+    fn wrap_mode_and_uv_scale_are_parsed() {
+        let source = "[baldosa]\ndiffuse = [200, 200, 200]\nuv_scale = [4.0, 2.0]\nwrap_mode = \"mirror\"";
+        let library = MaterialLibrary::parse(source, &mut TextureCache::new()).unwrap();
+        let tile = library.get("baldosa").unwrap();
+        assert_eq!(tile.wrap_mode, WrapMode::Mirror);
+        assert_eq!(tile.uv_scale, [4.0, 2.0]);
+        assert!(MaterialLibrary::parse("[m]\ndiffuse = [1, 2, 3]\nwrap_mode = \"wrap\"", &mut TextureCache::new()).is_err());
+    }
+
+    #[test]
+    fn valid_color_is_parsed() {
+        let library = MaterialLibrary::parse("[m]\ndiffuse = [10, 20, 255]", &mut TextureCache::new()).unwrap();
+        assert_eq!(library.get("m").unwrap().diffuse.to_hex(), 0x0A14FF);
+    }
+}
@@ -0,0 +1,127 @@
+use nalgebra_glm::Vec3;
+use std::fmt;
+use crate::material::Material;
+use crate::triangle::Triangle;
+
+// Índice único y caras trianguladas: cada 3 índices son un triángulo
+const LOAD_OPTIONS: tobj::LoadOptions = tobj::LoadOptions {
+    single_index: true,
+    triangulate: true,
+    ignore_points: true,
+    ignore_lines: true,
+};
+
+#[derive(Debug)]
+pub enum MeshError {
+    Load(tobj::LoadError),
+    ZeroNormal,
+}
+
+impl fmt::Display for MeshError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MeshError::Load(e) => write!(f, "Error al cargar el modelo: {}", e),
+            MeshError::ZeroNormal => write!(f, "El modelo tiene una normal de largo cero"),
+        }
+    }
+}
+
+impl From<tobj::LoadError> for MeshError {
+    fn from(e: tobj::LoadError) -> Self {
+        MeshError::Load(e)
+    }
+}
+
+pub struct Mesh;
+
+impl Mesh {
+    //Cargar .obj como lista de triangulos; los materiales .mtl se ignoran
+    pub fn load_obj(path: &str, material: Material) -> Result<Vec<Triangle>, MeshError> {
+        let (models, _) = tobj::load_obj(path, &LOAD_OPTIONS)?;
+        Self::triangles(&models, material)
+    }
+
+    fn triangles(models: &[tobj::Model], material: Material) -> Result<Vec<Triangle>, MeshError> {
+        let mut triangles = Vec::new();
+
+        for model in models {
+            let mesh = &model.mesh;
+            let vertex = |index: u32| {
+                let i = index as usize * 3;
+                Vec3::new(mesh.positions[i], mesh.positions[i + 1], mesh.positions[i + 2])
+            };
+
+            // Con índice único las normales van en el mismo orden que las posiciones
+            let has_normals = !mesh.normals.is_empty();
+            let mut normals = Vec::with_capacity(mesh.normals.len() / 3);
+            for normal in mesh.normals.chunks_exact(3) {
+                // Una normal de largo ~0 daría NaN al normalizar
+                let normal = Vec3::new(normal[0], normal[1], normal[2]);
+                if normal.magnitude() < 1e-6 {
+                    return Err(MeshError::ZeroNormal);
+                }
+                normals.push(normal.normalize());
+            }
+
+            for face in mesh.indices.chunks_exact(3) {
+                let vertex_normals = has_normals
+                    .then(|| [normals[face[0] as usize], normals[face[1] as usize], normals[face[2] as usize]]);
+                triangles.push(Triangle {
+                    a: vertex(face[0]),
+                    b: vertex(face[1]),
+                    c: vertex(face[2]),
+                    material: material.clone(),
+                    normals: vertex_normals,
+                });
+            }
+        }
+
+        Ok(triangles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ray_intersect::RayIntersect;
+
+    fn parse(source: &str) -> Result<Vec<Triangle>, MeshError> {
+        let (models, _) = tobj::load_obj_buf(&mut source.as_bytes(), &LOAD_OPTIONS, |_| {
+            Err(tobj::LoadError::OpenFileFailed)
+        })?;
+        Mesh::triangles(&models, Material::black())
+    }
+
+    #[test]
+    fn cube_obj_yields_twelve_triangles() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/models/cube.obj");
+        let triangles = Mesh::load_obj(path, Material::black()).unwrap();
+        assert_eq!(triangles.len(), 12);
+    }
+
+    #[test]
+    fn missing_file_is_a_load_error() {
+        let result = Mesh::load_obj("models/no_existe.obj", Material::black());
+        assert!(matches!(result, Err(MeshError::Load(_))));
+    }
+
+    #[test]
+    fn interpolated_normal_at_centroid() {
+        let source = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvn 1 0 1\nvn 0 1 1\nvn -1 -1 1\nf 1//1 2//2 3//3\n";
+        let triangles = parse(source).unwrap();
+        let centroid = (triangles[0].a + triangles[0].b + triangles[0].c) / 3.0;
+
+        let hit = triangles[0].ray_intersect(&(centroid + Vec3::new(0.0, 0.0, 1.0)), &Vec3::new(0.0, 0.0, -1.0));
+        assert!(hit.is_intersecting);
+        let expected = (Vec3::new(1.0, 0.0, 1.0).normalize()
+            + Vec3::new(0.0, 1.0, 1.0).normalize()
+            + Vec3::new(-1.0, -1.0, 1.0).normalize()).normalize();
+        assert!((hit.normal - expected).magnitude() < 1e-4);
+    }
+
+    #[test]
+    fn zero_length_normal_is_rejected() {
+        let source = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvn 0 0 0\nf 1//1 2//1 3//1\n";
+        assert!(matches!(parse(source), Err(MeshError::ZeroNormal)));
+    }
+}
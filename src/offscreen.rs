@@ -0,0 +1,60 @@
+use crate::camera::Camera;
+use crate::framebuffer::{Framebuffer, FramebufferError};
+use crate::light::Light;
+use crate::render;
+use crate::render_settings::RenderSettings;
+use crate::scene::Scene;
+
+// Todo lo necesario para renderizar sin ventana (pruebas, benchmarks)
+pub struct OffscreenRenderer {
+    pub framebuffer: Framebuffer,
+    pub scene: Scene,
+    pub camera: Camera,
+    pub light: Light,
+    pub settings: RenderSettings,
+    pub primary_rays: u64, //rayos primarios del último render_frame
+}
+
+impl OffscreenRenderer {
+    pub fn new(
+        width: usize,
+        height: usize,
+        scene: Scene,
+        camera: Camera,
+        light: Light,
+        settings: RenderSettings,
+    ) -> Result<Self, FramebufferError> {
+        Ok(OffscreenRenderer {
+            framebuffer: Framebuffer::try_new(width, height)?,
+            scene,
+            camera,
+            light,
+            settings,
+            primary_rays: 0,
+        })
+    }
+
+    // Pixeles 0xAARRGGBB, fila por fila
+    pub fn render_frame(&mut self) -> &[u32] {
+        self.primary_rays = render(&mut self.framebuffer, &self.scene, &self.camera, &self.light, &self.settings, None);
+        &self.framebuffer.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra_glm::Vec3;
+    use crate::color::Color;
+
+    #[test]
+    fn render_frame_returns_every_pixel() {
+        let camera = Camera::new(Vec3::new(0.0, 0.0, 5.0), Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+        let light = Light::new(Vec3::new(0.0, 5.0, 0.0), Color::new(255, 255, 255), 1.0);
+        let mut renderer = OffscreenRenderer::new(64, 64, Scene::new(), camera, light, RenderSettings::new()).unwrap();
+
+        assert_eq!(renderer.render_frame().len(), 64 * 64);
+        assert_eq!(renderer.primary_rays, 64 * 64);
+        assert!(OffscreenRenderer::new(0, 64, Scene::new(), renderer.camera, renderer.light, RenderSettings::new()).is_err());
+    }
+}
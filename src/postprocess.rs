@@ -0,0 +1,136 @@
+use nalgebra_glm::Vec3;
+
+pub struct Bloom {
+    pub threshold: f32,
+    pub radius: usize,
+}
+
+impl Bloom {
+    pub fn new(threshold: f32, radius: usize) -> Self {
+        Bloom {
+            threshold,
+            radius,
+        }
+    }
+
+    // Suma al buffer HDR el brillo que pasa el umbral, difuminado
+    pub fn apply(&self, hdr: &mut [Vec3], width: usize, height: usize) {
+        let bright: Vec<Vec3> = hdr.iter()
+            .map(|c| c.map(|channel| (channel - self.threshold).max(0.0)))
+            .collect();
+
+        let kernel = gaussian_kernel(self.radius);
+        let horizontal = blur(&bright, width, height, &kernel, true);
+        let blurred = blur(&horizontal, width, height, &kernel, false);
+
+        for (pixel, glow) in hdr.iter_mut().zip(blurred.iter()) {
+            *pixel += glow;
+        }
+    }
+}
+
+fn gaussian_kernel(radius: usize) -> Vec<f32> {
+    let sigma = (radius as f32 / 2.0).max(0.5);
+    let weights: Vec<f32> = (0..=2 * radius)
+        .map(|i| {
+            let x = i as f32 - radius as f32;
+            (-(x * x) / (2.0 * sigma * sigma)).exp()
+        })
+        .collect();
+    let total: f32 = weights.iter().sum();
+    weights.iter().map(|w| w / total).collect()
+}
+
+// Difuminado separable en una dirección, bordes recortados
+fn blur(source: &[Vec3], width: usize, height: usize, kernel: &[f32], horizontal: bool) -> Vec<Vec3> {
+    let radius = (kernel.len() / 2) as isize;
+    let mut result = vec![Vec3::zeros(); source.len()];
+
+    for y in 0..height {
+        for x in 0..width {
+            let mut sum = Vec3::zeros();
+            for (k, weight) in kernel.iter().enumerate() {
+                let offset = k as isize - radius;
+                let (sx, sy) = if horizontal {
+                    (x as isize + offset, y as isize)
+                } else {
+                    (x as isize, y as isize + offset)
+                };
+                if sx >= 0 && sy >= 0 && (sx as usize) < width && (sy as usize) < height {
+                    sum += source[sy as usize * width + sx as usize] * *weight;
+                }
+            }
+            result[y * width + x] = sum;
+        }
+    }
+
+    result
+}
+
+// Salto de profundidad relativo con los vecinos, en [0, 1] (1 = borde fuerte)
+// Un pixel con fondo junto a uno con objeto cuenta como borde completo
+pub fn depth_edges(depth: &[f32], width: usize, height: usize) -> Vec<f32> {
+    const GAIN: f32 = 10.0; //un salto del 10% ya es borde completo
+    let discontinuity = |a: f32, b: f32| {
+        match (a.is_finite(), b.is_finite()) {
+            (true, true) => ((a - b).abs() / a.min(b).max(1e-6) * GAIN).min(1.0),
+            (false, false) => 0.0,
+            _ => 1.0,
+        }
+    };
+
+    let mut edges = vec![0.0; depth.len()];
+    for y in 0..height {
+        for x in 0..width {
+            let index = y * width + x;
+            let center = depth[index];
+            let mut edge: f32 = 0.0;
+            if x + 1 < width {
+                edge = edge.max(discontinuity(center, depth[index + 1]));
+            }
+            if x > 0 {
+                edge = edge.max(discontinuity(center, depth[index - 1]));
+            }
+            if y + 1 < height {
+                edge = edge.max(discontinuity(center, depth[index + width]));
+            }
+            if y > 0 {
+                edge = edge.max(discontinuity(center, depth[index - width]));
+            }
+            edges[index] = edge;
+        }
+    }
+    edges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bright_pixel_spreads_to_its_neighbours() {
+        let (width, height) = (9, 9);
+        let mut hdr = vec![Vec3::new(0.2, 0.2, 0.2); width * height];
+        hdr[4 * width + 4] = Vec3::new(8.0, 8.0, 8.0);
+        Bloom::new(1.0, 2).apply(&mut hdr, width, height);
+
+        assert!(hdr[4 * width + 5].x > 0.2 && hdr[3 * width + 4].x > 0.2);
+        assert!(hdr[4 * width + 5].x > hdr[4 * width + 6].x); //cae con la distancia
+        assert_eq!(hdr[0], Vec3::new(0.2, 0.2, 0.2)); //fuera del radio no cambia
+    }
+
+    #[test]
+    fn edges_peak_at_the_depth_jump() {
+        //Fila con un salto de 2 a 5 entre x = 2 y x = 3, y fondo al final
+        let depth = [2.0, 2.0, 2.0, 5.0, 5.0, 5.0, f32::INFINITY];
+        let edges = depth_edges(&depth, depth.len(), 1);
+
+        assert_eq!(edges[0], 0.0);
+        assert_eq!(edges[2], 1.0);
+        assert_eq!(edges[3], 1.0);
+        assert_eq!(edges[4], 0.0);
+        assert_eq!(edges[5], 1.0); //junto al fondo
+        let max = edges.iter().cloned().fold(0.0, f32::max);
+        assert!(edges[1] < max);
+    }
+}
@@ -1,6 +1,7 @@
 
 use nalgebra_glm::Vec3;
 use crate::material::Material;
+use crate::aabb::Aabb;
 
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -11,7 +12,9 @@ pub struct Intersect {
     pub is_intersecting: bool,
     pub material: Material,
     pub u: f32,
-    pub v: f32
+    pub v: f32,
+    pub object_id: Option<usize>, //índice en la escena, lo asigna Scene
+    pub uv_derivatives: Option<(Vec3, Vec3)>, //dp/du y dp/dv antes de la transformación de uvs del material
 }
 
 impl Intersect {
@@ -23,10 +26,46 @@ impl Intersect {
             is_intersecting: true,
             material,
             u,
-            v
+            v,
+            object_id: None,
+            uv_derivatives: None,
         }
     }
 
+    // Cuánto se mueve el punto por unidad de u y de v según el mapeo de la primitiva
+    pub fn with_uv_derivatives(mut self, dp_du: Vec3, dp_dv: Vec3) -> Self {
+        self.uv_derivatives = Some((dp_du, dp_dv));
+        self
+    }
+
+    // Base tangente, bitangente y normal (ortonormal) del choque: la tangente sigue a u y la
+    // bitangente a v en la textura ya escalada y girada por el material
+    // Sin derivadas de la primitiva se elige un eje según la cara dominante de la normal
+    pub fn tbn(&self) -> (Vec3, Vec3, Vec3) {
+        let normal = self.normal;
+        let axis = if normal.x.abs() >= normal.y.abs() && normal.x.abs() >= normal.z.abs() {
+            Vec3::new(0.0, 0.0, 1.0) // Caras X
+        } else {
+            Vec3::new(1.0, 0.0, 0.0) // Caras Y y Z
+        };
+        let (dp_du, dp_dv) = match self.uv_derivatives {
+            Some((dp_du, dp_dv)) => self.material.transform_uv_derivatives(&dp_du, &dp_dv),
+            None => (axis, normal.cross(&axis)),
+        };
+
+        // Ortogonalizar por si la normal no es perpendicular (normales interpoladas)
+        let tangent = dp_du - normal * normal.dot(&dp_du);
+        let tangent = if tangent.magnitude_squared() > 1e-12 {
+            tangent.normalize()
+        } else {
+            (axis - normal * normal.dot(&axis)).normalize() // u no cambia aquí (centro de un disco)
+        };
+        // Con mapeos en espejo v crece al revés de normal x tangente
+        let bitangent = normal.cross(&tangent);
+        let bitangent = if bitangent.dot(&dp_dv) < 0.0 { -bitangent } else { bitangent };
+        (tangent, bitangent, normal)
+    }
+
     pub fn empty() -> Self {
         Intersect {
             point: Vec3::zeros(),
@@ -35,11 +74,29 @@ impl Intersect {
             is_intersecting: false,
             material: Material::black(),
             u: 0.0,
-            v: 0.0
+            v: 0.0,
+            object_id: None,
+            uv_derivatives: None,
         }
     }
 }
 
 pub trait RayIntersect {
     fn ray_intersect(&self, ray_origin: &Vec3, ray_direction: &Vec3) -> Intersect;
+
+    // Objetos desactivados se ignoran al trazar
+    fn is_enabled(&self) -> bool {
+        true
+    }
+
+    // Por defecto los objetos no se pueden mover
+    fn translate(&mut self, _offset: &Vec3) {}
+
+    // Avanzar animaciones al tiempo `time` (segundos)
+    fn update(&mut self, _time: f32) {}
+
+    // Caja que envuelve al objeto, None si no se conoce
+    fn bounding_box(&self) -> Option<Aabb> {
+        None
+    }
 }
\ No newline at end of file
@@ -1,6 +1,7 @@
 use nalgebra_glm::Vec3;
 use crate::ray_intersect::{RayIntersect, Intersect};
 use crate::material::Material;
+use crate::aabb::{Aabb, face_uv, face_uv_derivatives};
 
 pub struct RectangularPrism {
     pub center: Vec3,
@@ -8,87 +9,116 @@ pub struct RectangularPrism {
     pub height: f32,
     pub depth: f32,
     pub material: Material,
+    pub enabled: bool,
+}
+
+impl RectangularPrism {
+    pub fn bounds(&self) -> Aabb {
+        let half_extents = Vec3::new(self.width, self.height, self.depth) / 2.0;
+        Aabb::from_center(&self.center, &half_extents)
+    }
 }
 
 impl RayIntersect for RectangularPrism {
     fn ray_intersect(&self, ray_origin: &Vec3, ray_direction: &Vec3) -> Intersect {
-        let half_width = self.width / 2.0;
-        let half_height = self.height / 2.0;
-        let half_depth = self.depth / 2.0;
-
-        let min = self.center - Vec3::new(half_width, half_height, half_depth);
-        let max = self.center + Vec3::new(half_width, half_height, half_depth);
-        
-        let t_min = (min - ray_origin).component_div(ray_direction);
-        let t_max = (max - ray_origin).component_div(ray_direction);
-
-        let t_near = Vec3::new(
-            t_min.x.min(t_max.x),
-            t_min.y.min(t_max.y),
-            t_min.z.min(t_max.z),
-        );
-        let t_far = Vec3::new(
-            t_min.x.max(t_max.x),
-            t_min.y.max(t_max.y),
-            t_min.z.max(t_max.z),
-        );
-
-        let t_near_val = t_near.x.max(t_near.y).max(t_near.z);
-        let t_far_val = t_far.x.min(t_far.y).min(t_far.z);
-
-        if t_near_val > t_far_val || t_far_val < 0.0 {
-            return Intersect::empty(); // No hay intersección
-        }
+        let bounds = self.bounds();
+        let (min, max) = (bounds.min, bounds.max);
 
-        let t = if t_near_val < 0.0 { t_far_val } else { t_near_val };
+        let (t, face, inside) = match bounds.ray_intersect(ray_origin, ray_direction) {
+            Some(hit) => hit,
+            None => return Intersect::empty(), // No intersección
+        };
         let intersection_point = ray_origin + ray_direction * t;
 
-        let normal = calculate_normal(&intersection_point, &self.center, self.width, self.height, self.depth);
-
+        // Desde adentro la normal apunta hacia el interior (hacia el origen del rayo)
+        let normal = if inside { -face.normal() } else { face.normal() };
         // Calcular u y v para mapeo de texturas
-        let u = match normal.x {
-            1.0 => (intersection_point.z - min.z) / self.depth, // Cara derecha
-            -1.0 => (intersection_point.z - max.z) / self.depth, // Cara izquierda
-            _ => (intersection_point.x - min.x) / self.width,   // Para las caras Y y Z
-        };
-        
-        let v = match normal.y {
-            1.0 => (intersection_point.z - min.z) / self.depth, // Cara superior
-            -1.0 => (intersection_point.z - max.z) / self.depth, // Cara inferior
-            _ => (intersection_point.y - min.y) / self.height,   // Para las caras X y Z
-        };
-
+        let (u, v) = face_uv(face, &intersection_point, &min, &max);
+        let (u, v) = self.material.resolve_uv(u, v);
+        let (dp_du, dp_dv) = face_uv_derivatives(face, &min, &max);
         Intersect::new(intersection_point, normal, t, self.material.clone(), u, v) // Clonar material
+            .with_uv_derivatives(dp_du, dp_dv)
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn update(&mut self, time: f32) {
+        self.material.update(time);
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.bounds())
     }
 }
 
-// Vector normal
-fn calculate_normal(point: &Vec3, center: &Vec3, width: f32, height: f32, depth: f32) -> Vec3 {
-    let half_width = width / 2.0;
-    let half_height = height / 2.0;
-    let half_depth = depth / 2.0;
-    let x_diff = (point.x - center.x).abs() - half_width;
-    let y_diff = (point.y - center.y).abs() - half_height;
-    let z_diff = (point.z - center.z).abs() - half_depth;
-
-    // Cara más cercana
-    if x_diff >= y_diff && x_diff >= z_diff {
-        if point.x > center.x {
-            return Vec3::new(1.0, 0.0, 0.0); // Cara derecha
-        } else {
-            return Vec3::new(-1.0, 0.0, 0.0); // Cara izquierda
-        }
-    } else if y_diff >= x_diff && y_diff >= z_diff {
-        if point.y > center.y {
-            return Vec3::new(0.0, 1.0, 0.0); // Cara superior
-        } else {
-            return Vec3::new(0.0, -1.0, 0.0); // Cara inferior
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::Cube;
+
+    fn room() -> RectangularPrism {
+        RectangularPrism {
+            center: Vec3::new(0.0, 1.0, 0.0),
+            width: 4.0,
+            height: 2.0,
+            depth: 6.0,
+            material: Material::black(),
+            enabled: true,
         }
-    } else {
-        if point.z > center.z {
-            return Vec3::new(0.0, 0.0, 1.0); // Cara frontal
-        } else {
-            return Vec3::new(0.0, 0.0, -1.0); // Cara trasera
+    }
+
+    #[test]
+    fn ray_from_inside_gets_an_inward_normal() {
+        let room = room();
+        let hit = room.ray_intersect(&Vec3::new(0.5, 1.0, 0.0), &Vec3::new(1.0, 0.0, 0.0));
+        assert!((hit.point - Vec3::new(2.0, 1.0, 0.0)).magnitude() < 1e-5);
+        assert!((hit.distance - 1.5).abs() < 1e-5);
+        assert!((hit.normal - Vec3::new(-1.0, 0.0, 0.0)).magnitude() < 1e-5);
+
+        //El techo visto desde adentro mira hacia abajo; desde afuera hacia arriba
+        let ceiling = room.ray_intersect(&Vec3::new(0.0, 0.5, 1.0), &Vec3::new(0.0, 1.0, 0.0));
+        assert!((ceiling.normal - Vec3::new(0.0, -1.0, 0.0)).magnitude() < 1e-5);
+        let roof = room.ray_intersect(&Vec3::new(0.0, 5.0, 1.0), &Vec3::new(0.0, -1.0, 0.0));
+        assert!((roof.normal - Vec3::new(0.0, 1.0, 0.0)).magnitude() < 1e-5);
+    }
+
+    #[test]
+    fn axis_parallel_rays_hit_or_miss_cleanly() {
+        let room = room();
+        let along_x = Vec3::new(-1.0, 0.0, 0.0);
+        let hit = room.ray_intersect(&Vec3::new(5.0, 1.5, 2.0), &along_x);
+        assert!(hit.is_intersecting);
+        assert!((hit.point - Vec3::new(2.0, 1.5, 2.0)).magnitude() < 1e-5);
+        assert!(hit.point.iter().all(|c| c.is_finite()));
+        assert!(!room.ray_intersect(&Vec3::new(5.0, 2.5, 2.0), &along_x).is_intersecting);
+        assert!(!room.ray_intersect(&Vec3::new(5.0, 1.0, 3.5), &along_x).is_intersecting);
+
+        let along_z = Vec3::new(0.0, 0.0, 1.0);
+        assert!((room.ray_intersect(&Vec3::new(1.0, 0.5, -9.0), &along_z).distance - 6.0).abs() < 1e-5);
+        assert!(!room.ray_intersect(&Vec3::new(2.5, 0.5, -9.0), &along_z).is_intersecting);
+    }
+
+    #[test]
+    fn unit_prism_matches_unit_cube() {
+        let center = Vec3::new(0.3, -0.2, 1.0);
+        let cube = Cube { center, side_length: 1.0, material: Material::black(), enabled: true };
+        let prism = RectangularPrism { center, width: 1.0, height: 1.0, depth: 1.0, material: Material::black(), enabled: true };
+        let rays = [
+            (Vec3::new(3.0, 0.1, 1.2), Vec3::new(-1.0, 0.0, 0.0)),
+            (Vec3::new(0.5, 4.0, 0.8), Vec3::new(0.0, -1.0, 0.1)),
+            (Vec3::new(-2.0, -3.0, 5.0), Vec3::new(0.4, 0.5, -0.8)),
+            (Vec3::new(0.3, -0.2, 1.0), Vec3::new(0.2, 0.1, 1.0)), //desde adentro
+            (Vec3::new(3.0, 3.0, 3.0), Vec3::new(1.0, 0.0, 0.0)), //no choca
+        ];
+        for (origin, direction) in rays {
+            let direction = direction.normalize();
+            let (a, b) = (cube.ray_intersect(&origin, &direction), prism.ray_intersect(&origin, &direction));
+            assert_eq!(a.is_intersecting, b.is_intersecting);
+            assert_eq!((a.point, a.normal, a.distance, a.u, a.v), (b.point, b.normal, b.distance, b.u, b.v));
         }
+        assert_eq!(cube.bounding_box(), prism.bounding_box());
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,163 @@
+use crate::ambient_occlusion::AmbientOcclusion;
+use crate::tone_mapping::ToneMapping;
+use crate::postprocess::Bloom;
+
+// Muestreo adaptativo: después de min_samples se sigue hasta max_samples
+// solo si la varianza de la luminancia del pixel supera el umbral
+#[derive(Debug, Clone, Copy)]
+pub struct AdaptiveSampling {
+    pub min_samples: u32,
+    pub max_samples: u32,
+    pub variance_threshold: f32,
+}
+
+impl AdaptiveSampling {
+    pub fn new(min_samples: u32, max_samples: u32, variance_threshold: f32) -> Self {
+        AdaptiveSampling {
+            min_samples,
+            max_samples,
+            variance_threshold,
+        }
+    }
+
+    // sum y sum_squares acumulan la luminancia de `count` muestras
+    pub fn converged(&self, count: u32, sum: f32, sum_squares: f32) -> bool {
+        if count < self.min_samples.max(1) {
+            return false;
+        }
+        let mean = sum / count as f32;
+        let variance = (sum_squares / count as f32 - mean * mean).max(0.0);
+        variance <= self.variance_threshold
+    }
+}
+
+// Rectángulo de la imagen a renderizar, en pixeles
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Crop {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Crop {
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Self {
+        Crop { x, y, width, height }
+    }
+
+    // (x, y, x_end, y_end) dentro de una imagen de width x height
+    pub fn clamped(&self, width: usize, height: usize) -> (usize, usize, usize, usize) {
+        let x = self.x.min(width);
+        let y = self.y.min(height);
+        (x, y, x.saturating_add(self.width).min(width), y.saturating_add(self.height).min(height))
+    }
+}
+
+// Qué se muestra en pantalla: la imagen o una vista de depuración
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DebugView {
+    Shaded,
+    Edges, //bordes por saltos de profundidad entre vecinos
+    Normals, //normal del choque como color: 0.5 * n + 0.5
+}
+
+impl DebugView {
+    pub fn next(self) -> Self {
+        match self {
+            DebugView::Shaded => DebugView::Edges,
+            DebugView::Edges => DebugView::Normals,
+            DebugView::Normals => DebugView::Shaded,
+        }
+    }
+}
+
+const MIN_EXPOSURE: f32 = 1.0 / 16.0;
+const MAX_EXPOSURE: f32 = 16.0;
+const MIN_GAMMA: f32 = 0.5;
+const MAX_GAMMA: f32 = 3.0;
+
+pub struct RenderSettings {
+    pub tile_size: usize,
+    pub ambient_occlusion: Option<AmbientOcclusion>,
+    pub tone_mapping: ToneMapping,
+    pub bloom: Option<Bloom>, //opcional: B o --bloom
+    pub max_depth: u32, //rebotes de reflexión
+    pub shadow_samples: u32, //muestras por pixel para luces con radio, 1 = sombras duras
+    pub show_bounds: bool, //dibujar las cajas de los objetos encima
+    pub samples_per_pixel: u32, //mayor a 1 = supersampling con jitter de Halton
+    pub adaptive_sampling: Option<AdaptiveSampling>, //reemplaza a samples_per_pixel
+    pub seed: u64, //semilla de todo el muestreo: misma semilla, misma imagen
+    pub indirect_samples: u32, //rayos del rebote difuso, 0 = solo luz directa
+    pub exposure: f32, //multiplica el color HDR antes del tone mapping
+    pub gamma: f32, //curva después del tone mapping, 1 = sin cambio
+    pub debug_view: DebugView,
+    pub record_object_ids: bool, //llenar framebuffer.object_ids (para Scene::pick)
+    pub crop: Option<Crop>, //renderizar solo esta región, sin post-proceso
+}
+
+impl RenderSettings {
+    pub fn new() -> Self {
+        RenderSettings {
+            tile_size: 32,
+            ambient_occlusion: None,
+            tone_mapping: ToneMapping::Aces,
+            bloom: None,
+            max_depth: 3,
+            shadow_samples: 1,
+            show_bounds: false,
+            samples_per_pixel: 1,
+            adaptive_sampling: None,
+            seed: 0,
+            indirect_samples: 0,
+            exposure: 1.0,
+            gamma: 1.0,
+            debug_view: DebugView::Shaded,
+            record_object_ids: false,
+            crop: None,
+        }
+    }
+
+    // Ajustes interactivos, recortados a rangos razonables
+    pub fn adjust_exposure(&mut self, factor: f32) {
+        self.exposure = (self.exposure * factor).clamp(MIN_EXPOSURE, MAX_EXPOSURE);
+    }
+
+    pub fn adjust_gamma(&mut self, delta: f32) {
+        self.gamma = (self.gamma + delta).clamp(MIN_GAMMA, MAX_GAMMA);
+    }
+
+    // Máximo de muestras por pixel
+    pub fn max_samples(&self) -> u32 {
+        match self.adaptive_sampling {
+            Some(adaptive) => adaptive.max_samples.max(adaptive.min_samples).max(1),
+            None => self.samples_per_pixel.max(1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exposure_and_gamma_stay_in_range() {
+        let mut settings = RenderSettings::new();
+        settings.adjust_exposure(2.0);
+        assert_eq!(settings.exposure, 2.0);
+        for _ in 0..100 {
+            settings.adjust_exposure(1.1);
+        }
+        assert_eq!(settings.exposure, MAX_EXPOSURE);
+        for _ in 0..200 {
+            settings.adjust_exposure(1.0 / 1.1);
+        }
+        assert_eq!(settings.exposure, MIN_EXPOSURE);
+
+        settings.adjust_gamma(0.25);
+        assert_eq!(settings.gamma, 1.25);
+        settings.adjust_gamma(10.0);
+        assert_eq!(settings.gamma, MAX_GAMMA);
+        settings.adjust_gamma(-10.0);
+        assert_eq!(settings.gamma, MIN_GAMMA);
+    }
+}
@@ -0,0 +1,59 @@
+use nalgebra_glm::Vec3;
+use std::f32::consts::PI;
+
+// Olas procedurales: altura = suma de dos senos sobre las uvs, animados con el tiempo
+// Solo se inclina la normal, la geometría sigue plana
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ripples {
+    pub amplitude: f32, //inclinación máxima de cada onda
+    pub frequency: f32, //ondas por unidad de uv
+    pub speed: f32, //radianes de fase por segundo
+    pub time: f32, //lo actualiza Material::update
+}
+
+impl Ripples {
+    pub fn new(amplitude: f32, frequency: f32, speed: f32) -> Self {
+        Ripples {
+            amplitude,
+            frequency,
+            speed,
+            time: 0.0,
+        }
+    }
+
+    // Derivadas de la altura respecto a u y v
+    fn slope(&self, u: f32, v: f32) -> (f32, f32) {
+        let k = 2.0 * PI * self.frequency;
+        let phase = self.speed * self.time;
+
+        // Una onda en diagonal y otra más corta a lo largo de v, en sentido contrario
+        let first = self.amplitude * (k * (u + v) + phase).cos();
+        let second = 0.5 * self.amplitude * (1.7 * k * v - 1.3 * phase).cos();
+        (first, first + second)
+    }
+
+    // Normal inclinada según la pendiente de la superficie, siempre unitaria
+    pub fn perturb(&self, tangent: &Vec3, bitangent: &Vec3, normal: &Vec3, u: f32, v: f32) -> Vec3 {
+        let (du, dv) = self.slope(u, v);
+        (normal - tangent * du - bitangent * dv).normalize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    #[test]
+    fn normal_changes_over_time_and_stays_unit() {
+        let (tangent, bitangent, normal) = (Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0), Vec3::new(0.0, 1.0, 0.0));
+        let mut ripples = Ripples::new(0.08, 6.0, 1.5);
+        let at_zero = ripples.perturb(&tangent, &bitangent, &normal, 0.3, 0.2);
+        ripples.time = PI;
+        let at_pi = ripples.perturb(&tangent, &bitangent, &normal, 0.3, 0.2);
+
+        assert!((at_zero - at_pi).magnitude() > 1e-3);
+        assert!((at_zero.magnitude() - 1.0).abs() < 1e-5);
+        assert!((at_pi.magnitude() - 1.0).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,36 @@
+use nalgebra_glm::Vec3;
+
+// Generador pseudoaleatorio pequeño (SplitMix64): mismo estado, misma secuencia
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    // Secuencia propia para un punto de la escena: el ruido queda fijo en el mundo
+    // y no parpadea al mover la camara
+    pub fn for_point(seed: u64, point: &Vec3) -> Self {
+        let mut rng = Rng::new(seed);
+        for coordinate in [point.x, point.y, point.z] {
+            rng.state ^= coordinate.to_bits() as u64;
+            rng.next_u64();
+        }
+        rng
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    // Uniforme en [0, 1)
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
@@ -0,0 +1,59 @@
+use nalgebra_glm::Vec3;
+use std::f32::consts::PI;
+
+// Dirección en el hemisferio de `normal` con densidad proporcional al coseno
+// u1 y u2 en [0, 1): u1 controla la inclinación y u2 el giro alrededor de la normal
+pub fn cosine_weighted_hemisphere(normal: &Vec3, u1: f32, u2: f32) -> Vec3 {
+    let r = u1.sqrt();
+    let phi = 2.0 * PI * u2;
+    let (tangent, bitangent) = orthonormal_basis(normal);
+
+    let z = (1.0 - u1).max(0.0).sqrt();
+    (tangent * (r * phi.cos()) + bitangent * (r * phi.sin()) + normal * z).normalize()
+}
+
+// Punto `index` de `count` en espiral de Fibonacci: bien repartido para cualquier count
+// `rotation` en [0, 1) gira toda la espiral (distinta por pixel para cambiar bandas por ruido)
+pub fn spiral_2d(index: u32, count: u32, rotation: f32) -> (f32, f32) {
+    let golden_ratio = (5.0_f32.sqrt() - 1.0) / 2.0;
+    ((index as f32 + 0.5) / count as f32, (index as f32 * golden_ratio + rotation).fract())
+}
+
+// Dos vectores perpendiculares a la normal
+pub fn orthonormal_basis(normal: &Vec3) -> (Vec3, Vec3) {
+    let helper = if normal.x.abs() > 0.9 {
+        Vec3::new(0.0, 1.0, 0.0)
+    } else {
+        Vec3::new(1.0, 0.0, 0.0)
+    };
+    let tangent = normal.cross(&helper).normalize();
+    let bitangent = normal.cross(&tangent);
+    (tangent, bitangent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hemisphere_samples_are_cosine_distributed() {
+        let normal = Vec3::new(0.3, 0.8, -0.5).normalize();
+        let count = 4096;
+        let mut mean_cos = 0.0;
+        let mut upper_half = 0;
+        for i in 0..count {
+            let (u1, u2) = spiral_2d(i, count, 0.3);
+            let direction = cosine_weighted_hemisphere(&normal, u1, u2);
+            let cos = direction.dot(&normal);
+            assert!(cos >= 0.0);
+            assert!((direction.magnitude() - 1.0).abs() < 1e-5);
+            mean_cos += cos / count as f32;
+            if cos > 0.5f32.sqrt() {
+                upper_half += 1;
+            }
+        }
+        //Con densidad cos/pi: E[cos] = 2/3 y la mitad de las muestras cae a menos de 45°
+        assert!((mean_cos - 2.0 / 3.0).abs() < 0.01, "{}", mean_cos);
+        assert!((upper_half as f32 / count as f32 - 0.5).abs() < 0.02);
+    }
+}
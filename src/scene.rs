@@ -0,0 +1,212 @@
+use nalgebra_glm::Vec3;
+use crate::ray_intersect::{RayIntersect, Intersect};
+use crate::background::Background;
+use crate::color::Color;
+use crate::frustum::Frustum;
+use crate::aabb::Aabb;
+use crate::framebuffer::{Framebuffer, NO_OBJECT};
+
+pub struct Scene {
+    pub objects: Vec<Box<dyn RayIntersect>>,
+    pub background: Background,
+    pub ambient: Color, //luz mínima para que las sombras no queden negras
+    pub shadow_bias: f32, //separación de la superficie para los rayos de sombra (según la escala)
+    picked_ids: Vec<u32>, //object_ids guardados con store_object_ids, para pick
+    picked_width: usize,
+}
+
+impl Scene {
+    pub fn new() -> Self {
+        Scene {
+            objects: Vec::new(),
+            background: Background::Solid(Color::new(9, 20, 55)),
+            ambient: Color::new(9, 20, 55),
+            shadow_bias: 1e-3,
+            picked_ids: Vec::new(),
+            picked_width: 0,
+        }
+    }
+
+    // Devuelve el índice del objeto agregado
+    pub fn add_object(&mut self, object: Box<dyn RayIntersect>) -> usize {
+        self.objects.push(object);
+        self.objects.len() - 1
+    }
+
+    // Los objetos después de `index` bajan una posición
+    pub fn remove_object(&mut self, index: usize) -> Option<Box<dyn RayIntersect>> {
+        if index < self.objects.len() {
+            Some(self.objects.remove(index))
+        } else {
+            None
+        }
+    }
+
+    pub fn translate_object(&mut self, index: usize, offset: &Vec3) {
+        if let Some(object) = self.objects.get_mut(index) {
+            object.translate(offset);
+        }
+    }
+
+    pub fn update(&mut self, time: f32) {
+        for object in self.objects.iter_mut() {
+            object.update(time);
+        }
+    }
+
+    // Índices de los objetos que pueden verse; los que no tienen caja se incluyen siempre
+    pub fn visible_objects(&self, frustum: &Frustum) -> Vec<usize> {
+        self.objects
+            .iter()
+            .enumerate()
+            .filter(|(_, obj)| obj.is_enabled())
+            .filter(|(_, obj)| obj.bounding_box().is_none_or(|bounds| frustum.intersects(&bounds)))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    // Caja de todos los objetos activos; None si ninguno tiene caja
+    pub fn bounds(&self) -> Option<Aabb> {
+        self.objects
+            .iter()
+            .filter(|obj| obj.is_enabled())
+            .filter_map(|obj| obj.bounding_box())
+            .reduce(|bounds, other| bounds.union(&other))
+    }
+
+    pub fn nearest_hit(&self, ray_origin: &Vec3, ray_direction: &Vec3) -> Intersect {
+        closest_hit(self.objects.iter().enumerate(), ray_origin, ray_direction)
+    }
+
+    // Igual que nearest_hit pero solo contra los objetos indicados
+    pub fn nearest_hit_among(&self, indices: &[usize], ray_origin: &Vec3, ray_direction: &Vec3) -> Intersect {
+        closest_hit(indices.iter().map(|&index| (index, &self.objects[index])), ray_origin, ray_direction)
+    }
+
+    // Guarda los object_ids de un render con record_object_ids para consultarlos con pick
+    pub fn store_object_ids(&mut self, framebuffer: &Framebuffer) {
+        self.picked_ids.clone_from(&framebuffer.object_ids);
+        self.picked_width = framebuffer.width;
+    }
+
+    // Objeto visible en el pixel (x, y) según los ids guardados
+    pub fn pick(&self, x: usize, y: usize) -> Option<usize> {
+        if x >= self.picked_width {
+            return None;
+        }
+        let id = *self.picked_ids.get(y * self.picked_width + x)?;
+        if id == NO_OBJECT || id as usize >= self.objects.len() {
+            return None;
+        }
+        Some(id as usize)
+    }
+}
+
+// Choques a menos de esta distancia entre sí se consideran empatados (caras coplanares)
+const DEPTH_EPSILON: f32 = 1e-4;
+
+// Los objetos llegan en orden de índice: un choque nuevo solo reemplaza al actual si está
+// más cerca por más de DEPTH_EPSILON, así en un empate gana el de menor índice
+fn closest_hit<'a>(
+    objects: impl Iterator<Item = (usize, &'a Box<dyn RayIntersect>)>,
+    ray_origin: &Vec3,
+    ray_direction: &Vec3,
+) -> Intersect {
+    let mut intersect = Intersect::empty();
+    let mut zbuffer = f32::INFINITY;
+
+    for (index, object) in objects.filter(|(_, obj)| obj.is_enabled()) {
+        let mut tmp = object.ray_intersect(ray_origin, ray_direction);
+        if tmp.is_intersecting && tmp.distance < zbuffer - DEPTH_EPSILON {
+            zbuffer = tmp.distance;
+            tmp.object_id = Some(index);
+            intersect = tmp;
+        }
+    }
+
+    intersect
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Material;
+    use crate::rectangular_prism::RectangularPrism;
+    use crate::camera::Camera;
+
+    fn slab(top: f32) -> Box<dyn RayIntersect> {
+        Box::new(RectangularPrism {
+            center: Vec3::new(0.0, top - 0.5, 0.0),
+            width: 2.0,
+            height: 1.0,
+            depth: 2.0,
+            material: Material::black(),
+            enabled: true,
+        })
+    }
+
+    #[test]
+    fn coplanar_prisms_resolve_to_the_lowest_index() {
+        // Dos losas con la misma cara superior; la segunda apenas más arriba por redondeo
+        let mut scene = Scene::new();
+        scene.add_object(slab(0.0));
+        scene.add_object(slab(1e-5));
+
+        let origin = Vec3::new(0.3, 2.0, 0.1);
+        let direction = Vec3::new(0.0, -1.0, 0.0);
+        for _ in 0..3 {
+            assert_eq!(scene.nearest_hit(&origin, &direction).object_id, Some(0));
+            assert_eq!(scene.nearest_hit_among(&[0, 1], &origin, &direction).object_id, Some(0));
+        }
+    }
+
+    #[test]
+    fn clearly_closer_hit_wins() {
+        let mut scene = Scene::new();
+        scene.add_object(slab(0.0));
+        scene.add_object(slab(0.5));
+        let hit = scene.nearest_hit(&Vec3::new(0.0, 2.0, 0.0), &Vec3::new(0.0, -1.0, 0.0));
+        assert_eq!(hit.object_id, Some(1));
+    }
+
+    #[test]
+    fn bounds_cover_enabled_objects_only() {
+        let mut scene = Scene::new();
+        assert_eq!(scene.bounds(), None);
+        scene.add_object(slab(0.0));
+        scene.add_object(Box::new(RectangularPrism {
+            center: Vec3::new(4.0, 1.0, 0.0),
+            width: 2.0,
+            height: 2.0,
+            depth: 2.0,
+            material: Material::black(),
+            enabled: true,
+        }));
+        scene.add_object(Box::new(RectangularPrism {
+            center: Vec3::new(-9.0, 0.0, 0.0),
+            width: 1.0,
+            height: 1.0,
+            depth: 1.0,
+            material: Material::black(),
+            enabled: false,
+        }));
+
+        let bounds = scene.bounds().unwrap();
+        assert_eq!(bounds.min, Vec3::new(-1.0, -1.0, -1.0));
+        assert_eq!(bounds.max, Vec3::new(5.0, 2.0, 1.0));
+    }
+
+    fn block(center: Vec3) -> Box<dyn RayIntersect> {
+        Box::new(RectangularPrism { center, width: 1.0, height: 1.0, depth: 1.0, material: Material::black(), enabled: true })
+    }
+
+    #[test]
+    fn objects_behind_the_camera_are_not_visible() {
+        let mut scene = Scene::new();
+        let ahead = scene.add_object(block(Vec3::new(0.0, 0.0, -2.0)));
+        scene.add_object(block(Vec3::new(0.0, 0.0, 12.0)));
+        let camera = Camera::new(Vec3::new(0.0, 0.0, 5.0), Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+
+        assert_eq!(scene.visible_objects(&Frustum::from_camera(&camera, 1.5)), vec![ahead]);
+    }
+}
@@ -0,0 +1,108 @@
+use nalgebra_glm::Vec3;
+use std::collections::HashMap;
+
+// Subconjunto mínimo de TOML: [seccion], clave = valor, comentarios con #
+// Los valores se guardan como texto y se convierten al leerlos
+pub type Sections = HashMap<String, HashMap<String, String>>;
+
+pub fn parse(source: &str) -> Result<Sections, String> {
+    let mut sections: Sections = HashMap::new();
+    let mut current = String::new();
+
+    for (index, raw_line) in source.lines().enumerate() {
+        let line = strip_comment(raw_line).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            current = name.trim().to_string();
+            sections.entry(current.clone()).or_default();
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or(format!("Linea {}: se esperaba clave = valor", index + 1))?;
+        sections
+            .entry(current.clone())
+            .or_default()
+            .insert(key.trim().to_string(), value.trim().to_string());
+    }
+
+    Ok(sections)
+}
+
+// Todo lo que sigue a un # fuera de comillas
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (index, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..index],
+            _ => {}
+        }
+    }
+    line
+}
+
+pub fn get<'a>(sections: &'a Sections, section: &str, key: &str) -> Result<&'a str, String> {
+    sections
+        .get(section)
+        .and_then(|values| values.get(key))
+        .map(|value| value.as_str())
+        .ok_or(format!("Falta {}.{}", section, key))
+}
+
+pub fn parse_f32(value: &str) -> Result<f32, String> {
+    value
+        .parse::<f32>()
+        .ok()
+        .filter(|n| n.is_finite())
+        .ok_or(format!("Numero invalido: '{}'", value))
+}
+
+// Arreglo de números de largo fijo: [a, b, ...]
+pub fn parse_array<const N: usize>(value: &str) -> Result<[f32; N], String> {
+    let inner = value
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .ok_or(format!("Se esperaba un arreglo: '{}'", value))?;
+    let numbers: Vec<f32> = inner
+        .split(',')
+        .map(|n| parse_f32(n.trim()))
+        .collect::<Result<_, _>>()?;
+
+    numbers
+        .try_into()
+        .map_err(|_| format!("Se esperaban {} valores: '{}'", N, value))
+}
+
+pub fn parse_vec3(value: &str) -> Result<Vec3, String> {
+    let [x, y, z] = parse_array::<3>(value)?;
+    Ok(Vec3::new(x, y, z))
+}
+
+// Texto entre comillas dobles
+pub fn parse_string(value: &str) -> Result<String, String> {
+    value
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .map(|text| text.to_string())
+        .ok_or(format!("Se esperaba texto entre comillas: '{}'", value))
+}
+
+pub fn format_vec3(v: &Vec3) -> String {
+    format!("[{}, {}, {}]", v.x, v.y, v.z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_inside_quotes_is_not_a_comment() {
+        let sections = parse("[a]\ntexture = \"a#b.png\" # comentario\n").unwrap();
+        assert_eq!(get(&sections, "a", "texture").unwrap(), "\"a#b.png\"");
+    }
+}
@@ -0,0 +1,75 @@
+use nalgebra_glm::Vec3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ToneMapping {
+    Clamp,
+    Reinhard,
+    Aces,
+}
+
+impl ToneMapping {
+    // Lleva un color HDR (1.0 = blanco) al rango [0, 1]
+    pub fn apply(self, color: &Vec3) -> Vec3 {
+        match self {
+            ToneMapping::Clamp => color.map(|c| c.clamp(0.0, 1.0)),
+            ToneMapping::Reinhard => color.map(|c| {
+                let c = c.max(0.0);
+                c / (1.0 + c)
+            }),
+            // Aproximación de Narkowicz para ACES
+            ToneMapping::Aces => color.map(|c| {
+                let c = c.max(0.0);
+                ((c * (2.51 * c + 0.03)) / (c * (2.43 * c + 0.59) + 0.14)).clamp(0.0, 1.0)
+            }),
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            ToneMapping::Clamp => ToneMapping::Reinhard,
+            ToneMapping::Reinhard => ToneMapping::Aces,
+            ToneMapping::Aces => ToneMapping::Clamp,
+        }
+    }
+}
+
+// Curva de gamma después del tone mapping: mayor a 1 aclara los medios tonos, 1 no cambia nada
+pub fn apply_gamma(color: &Vec3, gamma: f32) -> Vec3 {
+    if gamma == 1.0 {
+        return *color;
+    }
+    color.map(|c| c.max(0.0).powf(1.0 / gamma))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framebuffer::Framebuffer;
+
+    #[test]
+    fn bright_emission_stays_below_white_under_reinhard() {
+        let mut framebuffer = Framebuffer::new(1, 1);
+        framebuffer.hdr[0] = Vec3::new(50.0, 50.0, 50.0);
+
+        framebuffer.tone_map(ToneMapping::Reinhard, 1.0);
+        let channel = framebuffer.buffer[0] & 0xFF;
+        assert!((240..255).contains(&channel), "{}", channel);
+
+        framebuffer.tone_map(ToneMapping::Clamp, 1.0);
+        assert_eq!(framebuffer.buffer[0] & 0xFF, 255); //recortar sí llega a 255
+    }
+
+    #[test]
+    fn curves_are_monotonic_from_black_to_their_limit() {
+        let inputs: Vec<f32> = (0..=200).map(|i| i as f32 * 0.1).collect();
+        //(curva, valor al final del rango probado)
+        for (tone_mapping, top) in [(ToneMapping::Clamp, 1.0), (ToneMapping::Reinhard, 20.0 / 21.0), (ToneMapping::Aces, 1.0)] {
+            let outputs: Vec<f32> = inputs.iter().map(|&c| tone_mapping.apply(&Vec3::new(c, c, c)).x).collect();
+            assert_eq!(outputs[0], 0.0, "{:?}", tone_mapping);
+            assert!(outputs.windows(2).all(|pair| pair[0] <= pair[1]), "{:?}", tone_mapping);
+            assert!((outputs[outputs.len() - 1] - top).abs() < 1e-3, "{:?}", tone_mapping);
+        }
+        //Reinhard nunca llega a 1, solo se acerca
+        assert!(ToneMapping::Reinhard.apply(&Vec3::new(1e4, 1e4, 1e4)).x < 1.0);
+    }
+}
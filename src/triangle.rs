@@ -0,0 +1,102 @@
+use nalgebra_glm::Vec3;
+use crate::ray_intersect::{RayIntersect, Intersect};
+use crate::material::Material;
+use crate::aabb::Aabb;
+
+pub struct Triangle {
+    pub a: Vec3,
+    pub b: Vec3,
+    pub c: Vec3,
+    pub material: Material,
+    pub normals: Option<[Vec3; 3]>, //normales de a, b y c para sombreado suave
+}
+
+impl RayIntersect for Triangle {
+    // Möller–Trumbore
+    fn ray_intersect(&self, ray_origin: &Vec3, ray_direction: &Vec3) -> Intersect {
+        const EPSILON: f32 = 1e-6;
+
+        let edge1 = self.b - self.a;
+        let edge2 = self.c - self.a;
+
+        let p = ray_direction.cross(&edge2);
+        let det = edge1.dot(&p);
+
+        // Rayo paralelo o cara trasera
+        if det < EPSILON {
+            return Intersect::empty();
+        }
+
+        let inv_det = 1.0 / det;
+        let s = ray_origin - self.a;
+        let u = s.dot(&p) * inv_det;
+        if !(0.0..=1.0).contains(&u) {
+            return Intersect::empty();
+        }
+
+        let q = s.cross(&edge1);
+        let v = ray_direction.dot(&q) * inv_det;
+        if v < 0.0 || u + v > 1.0 {
+            return Intersect::empty();
+        }
+
+        let t = edge2.dot(&q) * inv_det;
+        if t < EPSILON {
+            return Intersect::empty(); // Detrás del origen
+        }
+
+        let intersection_point = ray_origin + ray_direction * t;
+        let normal = match self.normals {
+            // Interpolar con las mismas baricéntricas del choque
+            Some([normal_a, normal_b, normal_c]) => {
+                (normal_a * (1.0 - u - v) + normal_b * u + normal_c * v).normalize()
+            }
+            None => edge1.cross(&edge2).normalize(),
+        };
+
+        // u y v baricéntricas como coordenadas de textura (con escala, giro y wrap del material)
+        let (u, v) = self.material.resolve_uv(u, v);
+        Intersect::new(intersection_point, normal, t, self.material.clone(), u, v)
+            .with_uv_derivatives(edge1, edge2) // u crece de a hacia b, v de a hacia c
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let min = self.a.inf(&self.b).inf(&self.c);
+        let max = self.a.sup(&self.b).sup(&self.c);
+        Some(Aabb { min, max })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle(material: Material) -> Triangle {
+        Triangle {
+            a: Vec3::new(0.0, 0.0, 0.0),
+            b: Vec3::new(1.0, 0.0, 0.0),
+            c: Vec3::new(0.0, 1.0, 0.0),
+            material,
+            normals: None,
+        }
+    }
+
+    fn hit_at(triangle: &Triangle, x: f32, y: f32) -> Intersect {
+        triangle.ray_intersect(&Vec3::new(x, y, 1.0), &Vec3::new(0.0, 0.0, -1.0))
+    }
+
+    #[test]
+    fn hit_returns_barycentric_uvs() {
+        let hit = hit_at(&triangle(Material::black()), 0.2, 0.3);
+        assert!(hit.is_intersecting);
+        assert!((hit.u - 0.2).abs() < 1e-5 && (hit.v - 0.3).abs() < 1e-5);
+    }
+
+    #[test]
+    fn uvs_go_through_the_material_transform() {
+        let mut material = Material::black();
+        material.uv_scale = [2.0, 2.0];
+        let hit = hit_at(&triangle(material), 0.2, 0.3);
+        assert!((hit.u - 0.4).abs() < 1e-5 && (hit.v - 0.6).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,175 @@
+use nalgebra_glm::Vec3;
+use crate::ray_intersect::{RayIntersect, Intersect};
+use crate::material::Material;
+use crate::aabb::Aabb;
+
+// Techo a dos aguas: base rectangular en y = base_center.y y cumbrera a lo largo de z
+// a `height` sobre el centro de la base
+pub struct TriangularPrism {
+    pub base_center: Vec3,
+    pub width: f32, //en x, de alero a alero
+    pub depth: f32, //en z, largo de la cumbrera
+    pub height: f32,
+    pub material: Material,
+    pub enabled: bool,
+}
+
+#[derive(Clone, Copy)]
+enum PrismFace {
+    Bottom,
+    LeftSlope,
+    RightSlope,
+    Back,
+    Front,
+}
+
+impl TriangularPrism {
+    // Semiespacios n·p <= d (en coordenadas locales) cuya intersección es el prisma
+    fn planes(&self) -> [(PrismFace, Vec3, f32); 5] {
+        let half_width = self.width / 2.0;
+        let half_depth = self.depth / 2.0;
+        let left = Vec3::new(-self.height, half_width, 0.0).normalize();
+        let right = Vec3::new(self.height, half_width, 0.0).normalize();
+        [
+            (PrismFace::Bottom, Vec3::new(0.0, -1.0, 0.0), 0.0),
+            (PrismFace::LeftSlope, left, left.y * self.height),
+            (PrismFace::RightSlope, right, right.y * self.height),
+            (PrismFace::Back, Vec3::new(0.0, 0.0, -1.0), half_depth),
+            (PrismFace::Front, Vec3::new(0.0, 0.0, 1.0), half_depth),
+        ]
+    }
+
+    // dp/du y dp/dv de face_uv en cada cara; en las pendientes v sube por el techo
+    fn face_uv_derivatives(&self, face: PrismFace) -> (Vec3, Vec3) {
+        let half_width = self.width / 2.0;
+        let along_x = Vec3::new(self.width, 0.0, 0.0);
+        let along_z = Vec3::new(0.0, 0.0, self.depth);
+        let up = Vec3::new(0.0, self.height, 0.0);
+        match face {
+            PrismFace::Bottom => (along_x, along_z),
+            PrismFace::LeftSlope => (along_z, Vec3::new(half_width, self.height, 0.0)),
+            PrismFace::RightSlope => (-along_z, Vec3::new(-half_width, self.height, 0.0)),
+            PrismFace::Back => (-along_x, up),
+            PrismFace::Front => (along_x, up),
+        }
+    }
+
+    fn face_uv(&self, face: PrismFace, point: &Vec3) -> (f32, f32) {
+        let u_x = point.x / self.width + 0.5;
+        let u_z = point.z / self.depth + 0.5;
+        let v_y = point.y / self.height;
+        match face {
+            PrismFace::Bottom => (u_x, u_z),
+            PrismFace::LeftSlope => (u_z, v_y), //de alero (0) a cumbrera (1)
+            PrismFace::RightSlope => (1.0 - u_z, v_y),
+            PrismFace::Back => (1.0 - u_x, v_y),
+            PrismFace::Front => (u_x, v_y),
+        }
+    }
+}
+
+impl RayIntersect for TriangularPrism {
+    fn ray_intersect(&self, ray_origin: &Vec3, ray_direction: &Vec3) -> Intersect {
+        const EPSILON: f32 = 1e-6;
+
+        let origin = ray_origin - self.base_center;
+        let mut enter: Option<(f32, PrismFace, Vec3)> = None;
+        let mut exit: Option<(f32, PrismFace, Vec3)> = None;
+
+        // Recortar el rayo contra cada semiespacio
+        for (face, normal, offset) in self.planes() {
+            let denominator = normal.dot(ray_direction);
+            let distance = offset - normal.dot(&origin);
+            if denominator.abs() < EPSILON {
+                if distance < 0.0 {
+                    return Intersect::empty(); // Paralelo y afuera
+                }
+                continue;
+            }
+            let t = distance / denominator;
+            if denominator < 0.0 {
+                if enter.is_none_or(|(enter_t, ..)| t > enter_t) {
+                    enter = Some((t, face, normal));
+                }
+            } else if exit.is_none_or(|(exit_t, ..)| t < exit_t) {
+                exit = Some((t, face, normal));
+            }
+        }
+
+        let (Some(enter), Some(exit)) = (enter, exit) else {
+            return Intersect::empty();
+        };
+        if enter.0 > exit.0 || exit.0 < EPSILON {
+            return Intersect::empty(); // No intersección
+        }
+
+        // Desde adentro el primer choque es la salida, con la normal hacia el interior
+        let (t, face, normal) = if enter.0 > EPSILON { enter } else { (exit.0, exit.1, -exit.2) };
+        let local_point = origin + ray_direction * t;
+        let (u, v) = self.face_uv(face, &local_point);
+        let (u, v) = self.material.resolve_uv(u, v);
+        let (dp_du, dp_dv) = self.face_uv_derivatives(face);
+        Intersect::new(ray_origin + ray_direction * t, normal, t, self.material.clone(), u, v)
+            .with_uv_derivatives(dp_du, dp_dv)
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn update(&mut self, time: f32) {
+        self.material.update(time);
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let half_extents = Vec3::new(self.width, self.height, self.depth) / 2.0;
+        let center = self.base_center + Vec3::new(0.0, self.height / 2.0, 0.0);
+        Some(Aabb::from_center(&center, &half_extents))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roof() -> TriangularPrism {
+        TriangularPrism {
+            base_center: Vec3::new(0.0, 1.0, 0.0),
+            width: 2.0,
+            depth: 4.0,
+            height: 1.0,
+            material: Material::black(),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn slopes_tilt_the_normal_off_vertical() {
+        let roof = roof();
+        let down = Vec3::new(0.0, -1.0, 0.0);
+        let left = roof.ray_intersect(&Vec3::new(-0.5, 5.0, 1.0), &down);
+        assert!((left.point - Vec3::new(-0.5, 1.5, 1.0)).magnitude() < 1e-5);
+        assert!((left.normal - Vec3::new(-1.0, 1.0, 0.0).normalize()).magnitude() < 1e-5);
+
+        let right = roof.ray_intersect(&Vec3::new(0.5, 5.0, -1.0), &down);
+        assert!((right.point - Vec3::new(0.5, 1.5, -1.0)).magnitude() < 1e-5);
+        assert!((right.normal - Vec3::new(1.0, 1.0, 0.0).normalize()).magnitude() < 1e-5);
+
+        assert!(!roof.ray_intersect(&Vec3::new(1.5, 5.0, 0.0), &down).is_intersecting);
+    }
+
+    #[test]
+    fn base_and_gables_have_axis_normals() {
+        let roof = roof();
+        let bottom = roof.ray_intersect(&Vec3::new(0.3, -5.0, 1.0), &Vec3::new(0.0, 1.0, 0.0));
+        assert!((bottom.point - Vec3::new(0.3, 1.0, 1.0)).magnitude() < 1e-5);
+        assert!((bottom.normal - Vec3::new(0.0, -1.0, 0.0)).magnitude() < 1e-5);
+
+        let front = roof.ray_intersect(&Vec3::new(0.0, 1.2, 5.0), &Vec3::new(0.0, 0.0, -1.0));
+        assert!((front.point - Vec3::new(0.0, 1.2, 2.0)).magnitude() < 1e-5);
+        assert!((front.normal - Vec3::new(0.0, 0.0, 1.0)).magnitude() < 1e-5);
+
+        //Sobre el hastial, por encima de la cumbrera, no choca
+        assert!(!roof.ray_intersect(&Vec3::new(0.0, 2.2, 5.0), &Vec3::new(0.0, 0.0, -1.0)).is_intersecting);
+    }
+}
@@ -0,0 +1,118 @@
+use nalgebra_glm::Vec3;
+use std::fs;
+use std::io;
+use crate::camera::Camera;
+use crate::light::Light;
+use crate::toml_lite::{self, format_vec3, parse_f32, parse_vec3};
+
+pub const VIEW_CONFIG_PATH: &str = "diorama.toml";
+
+// Encuadre guardado entre ejecuciones (F5)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewConfig {
+    pub eye: Vec3,
+    pub center: Vec3,
+    pub up: Vec3,
+    pub fov: f32,
+    pub light_position: Vec3,
+    pub light_intensity: f32,
+}
+
+impl ViewConfig {
+    pub fn capture(camera: &Camera, light: &Light) -> Self {
+        ViewConfig {
+            eye: camera.eye,
+            center: camera.center,
+            up: camera.up,
+            fov: camera.fov(),
+            light_position: light.position,
+            light_intensity: light.intensity,
+        }
+    }
+
+    // La camara nueva toma este encuadre como inicial (R vuelve aquí)
+    pub fn camera(&self) -> Camera {
+        let mut camera = Camera::new(self.eye, self.center, self.up);
+        camera.set_fov(self.fov);
+        camera
+    }
+
+    pub fn apply_to_light(&self, light: &mut Light) {
+        light.position = self.light_position;
+        light.intensity = self.light_intensity;
+    }
+
+    pub fn to_toml(&self) -> String {
+        format!(
+            "[camera]\neye = {}\ncenter = {}\nup = {}\nfov = {}\n\n[light]\nposition = {}\nintensity = {}\n",
+            format_vec3(&self.eye),
+            format_vec3(&self.center),
+            format_vec3(&self.up),
+            self.fov,
+            format_vec3(&self.light_position),
+            self.light_intensity,
+        )
+    }
+
+    pub fn from_toml(source: &str) -> Result<Self, String> {
+        let sections = toml_lite::parse(source)?;
+        let field = |section: &str, key: &str| toml_lite::get(&sections, section, key);
+
+        Ok(ViewConfig {
+            eye: parse_vec3(field("camera", "eye")?)?,
+            center: parse_vec3(field("camera", "center")?)?,
+            up: parse_vec3(field("camera", "up")?)?,
+            fov: parse_f32(field("camera", "fov")?)?,
+            light_position: parse_vec3(field("light", "position")?)?,
+            light_intensity: parse_f32(field("light", "intensity")?)?,
+        })
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        fs::write(path, self.to_toml())
+    }
+
+    // Sin archivo no hay nada que restaurar; si está mal formado se avisa y se ignora
+    pub fn load(path: &str) -> Option<Self> {
+        let source = fs::read_to_string(path).ok()?;
+        match Self::from_toml(&source) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                eprintln!("Ignorando {}: {}", path, e);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+
+    #[test]
+    fn save_then_load_restores_camera_and_light() {
+        let mut camera = Camera::new(Vec3::new(1.5, 2.0, -3.25), Vec3::new(0.0, 0.5, 0.0), Vec3::new(0.0, 1.0, 0.0));
+        camera.set_fov(0.9);
+        let light = Light::new(Vec3::new(-2.0, 4.5, 0.125), Color::new(255, 255, 255), 1.3);
+        let config = ViewConfig::capture(&camera, &light);
+
+        let path = std::env::temp_dir().join(format!("diorama_view_{}.toml", std::process::id()));
+        let path = path.to_string_lossy();
+        config.save(&path).unwrap();
+        let loaded = ViewConfig::load(&path);
+        fs::remove_file(&*path).unwrap();
+
+        assert_eq!(loaded, Some(config));
+        let restored = loaded.unwrap().camera();
+        assert_eq!(restored.eye, camera.eye);
+        assert_eq!(restored.center, camera.center);
+        assert_eq!(restored.fov(), camera.fov());
+    }
+
+    #[test]
+    fn missing_or_malformed_file_loads_nothing() {
+        assert_eq!(ViewConfig::load("no_existe.toml"), None);
+        assert!(ViewConfig::from_toml("[camera]\neye = 1, 2\n").is_err());
+    }
+}